@@ -24,10 +24,30 @@ use gtk::prelude::{GtkMenuExt, GtkMenuItemExt, MenuShellExt, WidgetExt};
 
 use super::keycodes;
 use super::window::WindowHandle;
-use crate::common_util::strip_access_key;
+use crate::common_util::split_access_key;
 use crate::hotkey::{HotKey, RawMods};
 use crate::keyboard::{KbKey, Modifiers};
 
+/// Convert the access keys of a menu string to GTK mnemonics.
+///
+/// Changes "E&xit" to "E_xit", so that GTK underlines the "x" and activates
+/// the item when it is pressed with Alt. Actual ampersands are escaped as "&&",
+/// and actual underscores are escaped for GTK.
+fn access_key_to_mnemonic(raw_menu_text: &str) -> String {
+    let (text, access_key) = split_access_key(raw_menu_text);
+    let mut result = String::with_capacity(text.len() + 1);
+    for (idx, c) in text.char_indices() {
+        if Some(idx) == access_key {
+            result.push('_');
+        }
+        if c == '_' {
+            result.push('_');
+        }
+        result.push(c);
+    }
+    result
+}
+
 #[derive(Default, Debug)]
 pub struct Menu {
     items: Vec<MenuItem>,
@@ -58,7 +78,7 @@ impl Menu {
     pub fn add_dropdown(&mut self, menu: Menu, text: &str, _enabled: bool) {
         // TODO: implement enabled dropdown
         self.items
-            .push(MenuItem::SubMenu(strip_access_key(text), menu));
+            .push(MenuItem::SubMenu(access_key_to_mnemonic(text), menu));
     }
 
     pub fn add_item(
@@ -70,7 +90,7 @@ impl Menu {
         enabled: bool,
     ) {
         self.items.push(MenuItem::Entry {
-            name: strip_access_key(text),
+            name: access_key_to_mnemonic(text),
             id,
             key: key.cloned(),
             selected,
@@ -98,18 +118,18 @@ impl Menu {
                     enabled,
                 } => {
                     if let Some(state) = selected {
-                        let item = CheckMenuItem::with_label(&name);
+                        let item = CheckMenuItem::with_mnemonic(&name);
                         if state {
                             item.activate();
                         }
                         add_menu_entry(menu, handle, accel_group, &item, id, key, enabled);
                     } else {
-                        let entry = GtkMenuItem::with_label(&name);
+                        let entry = GtkMenuItem::with_mnemonic(&name);
                         add_menu_entry(menu, handle, accel_group, &entry, id, key, enabled);
                     }
                 }
                 MenuItem::SubMenu(name, submenu) => {
-                    let item = GtkMenuItem::with_label(&name);
+                    let item = GtkMenuItem::with_mnemonic(&name);
                     item.set_submenu(Some(&submenu.into_gtk_menu(handle, accel_group)));
 
                     menu.append(&item);
@@ -207,3 +227,17 @@ fn modifiers_to_gdk_modifier_type(raw_modifiers: RawMods) -> ModifierType {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn access_keys_become_mnemonics() {
+        assert_eq!(access_key_to_mnemonic("&Exit").as_str(), "_Exit");
+        assert_eq!(access_key_to_mnemonic("&&Exit").as_str(), "&Exit");
+        assert_eq!(access_key_to_mnemonic("E&xit").as_str(), "E_xit");
+        assert_eq!(access_key_to_mnemonic("Save_&As").as_str(), "Save___As");
+    }
+}
@@ -263,6 +263,12 @@ impl WindowBuilder {
         self.always_on_top = always_on_top;
     }
 
+    pub fn set_content_protected(&mut self, protected: bool) {
+        if protected {
+            warn!("WindowBuilder::set_content_protected is unimplemented for GTK");
+        }
+    }
+
     pub fn set_transparent(&mut self, transparent: bool) {
         self.transparent = transparent;
     }
@@ -1133,6 +1139,10 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_content_protected(&self, _protected: bool) {
+        warn!("WindowHandle::set_content_protected is unimplemented for GTK");
+    }
+
     pub fn handle_titlebar(&self, val: bool) {
         if let Some(state) = self.state.upgrade() {
             state.handle_titlebar.set(val);
@@ -1424,6 +1434,7 @@ fn make_gdk_cursor(cursor: &Cursor, gdk_window: &Window) -> Option<gtk::gdk::Cur
                 Cursor::NotAllowed => "not-allowed",
                 Cursor::ResizeLeftRight => "ew-resize",
                 Cursor::ResizeUpDown => "ns-resize",
+                Cursor::Wait => "wait",
                 Cursor::Custom(_) => unreachable!(),
             },
         )
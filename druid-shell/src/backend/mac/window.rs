@@ -147,6 +147,7 @@ pub(crate) struct WindowBuilder {
     show_titlebar: bool,
     transparent: bool,
     always_on_top: bool,
+    content_protected: bool,
 }
 
 #[derive(Clone)]
@@ -202,6 +203,7 @@ impl WindowBuilder {
             show_titlebar: true,
             transparent: false,
             always_on_top: false,
+            content_protected: false,
         }
     }
 
@@ -237,6 +239,10 @@ impl WindowBuilder {
         self.always_on_top = always_on_top;
     }
 
+    pub fn set_content_protected(&mut self, protected: bool) {
+        self.content_protected = protected;
+    }
+
     pub fn set_position(&mut self, position: Point) {
         self.position = Some(position)
     }
@@ -342,6 +348,10 @@ impl WindowBuilder {
                 handle.set_always_on_top(self.always_on_top);
             }
 
+            if self.content_protected {
+                handle.set_content_protected(true);
+            }
+
             // set_window_state above could have invalidated the frame size
             let frame = NSView::frame(content_view);
 
@@ -1107,6 +1117,8 @@ impl WindowHandle {
                 Cursor::NotAllowed => msg_send![nscursor, operationNotAllowedCursor],
                 Cursor::ResizeLeftRight => msg_send![nscursor, resizeLeftRightCursor],
                 Cursor::ResizeUpDown => msg_send![nscursor, resizeUpDownCursor],
+                // There is no public busy cursor, macOS shows one when the app hangs.
+                Cursor::Wait => msg_send![nscursor, arrowCursor],
                 // TODO: support custom cursors
                 Cursor::Custom(_) => msg_send![nscursor, arrowCursor],
             };
@@ -1346,6 +1358,15 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_content_protected(&self, protected: bool) {
+        unsafe {
+            // NSWindowSharingNone and NSWindowSharingReadOnly
+            let sharing_type: NSUInteger = if protected { 0 } else { 1 };
+            let window: id = msg_send![*self.nsview.load(), window];
+            let () = msg_send![window, setSharingType: sharing_type];
+        }
+    }
+
     fn set_level(&self, level: WindowLevel) {
         unsafe {
             let level = levels::as_raw_window_level(level);
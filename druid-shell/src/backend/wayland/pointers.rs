@@ -229,6 +229,7 @@ impl Pointer {
             mouse::Cursor::NotAllowed => self.unpack_image_buffer("X_cursor"),
             mouse::Cursor::ResizeLeftRight => self.unpack_image_buffer("row-resize"),
             mouse::Cursor::ResizeUpDown => self.unpack_image_buffer("col-resize"),
+            mouse::Cursor::Wait => self.unpack_image_buffer("watch"),
             mouse::Cursor::Pointer => self.unpack_image_buffer("pointer"),
             mouse::Cursor::Custom(_) => {
                 tracing::warn!("custom cursors not implemented");
@@ -120,6 +120,10 @@ impl WindowHandle {
         tracing::warn!("set_always_on_top is unimplemented on wayland");
     }
 
+    pub fn set_content_protected(&self, _protected: bool) {
+        tracing::warn!("set_content_protected is unimplemented on wayland");
+    }
+
     pub fn set_input_region(&self, region: Option<Region>) {
         self.inner.surface.set_input_region(region);
     }
@@ -397,6 +401,12 @@ impl WindowBuilder {
         );
     }
 
+    pub fn set_content_protected(&mut self, protected: bool) {
+        if protected {
+            tracing::warn!("set_content_protected is unimplemented on wayland");
+        }
+    }
+
     pub fn set_transparent(&mut self, _transparent: bool) {
         tracing::warn!(
             "set_transparent unimplemented for wayland, it allows transparency by default"
@@ -400,6 +400,10 @@ impl WindowBuilder {
         // Ignored
     }
 
+    pub fn set_content_protected(&mut self, _protected: bool) {
+        // Ignored
+    }
+
     pub fn set_window_state(&self, _state: window::WindowState) {
         // Ignored
     }
@@ -521,6 +525,10 @@ impl WindowHandle {
         warn!("WindowHandle::set_always_on_top unimplemented for web");
     }
 
+    pub fn set_content_protected(&self, _protected: bool) {
+        warn!("WindowHandle::set_content_protected unimplemented for web");
+    }
+
     pub fn get_position(&self) -> Point {
         warn!("WindowHandle::get_position unimplemented for web.");
         Point::new(0.0, 0.0)
@@ -809,6 +817,7 @@ fn set_cursor(canvas: &web_sys::HtmlCanvasElement, cursor: &Cursor) {
                 Cursor::NotAllowed => "not-allowed",
                 Cursor::ResizeLeftRight => "ew-resize",
                 Cursor::ResizeUpDown => "ns-resize",
+                Cursor::Wait => "wait",
                 // TODO: support custom cursors
                 Cursor::Custom(_) => "default",
             },
@@ -100,6 +100,7 @@ pub(crate) struct WindowBuilder {
     position: Option<Point>,
     level: Option<WindowLevel>,
     always_on_top: bool,
+    content_protected: bool,
     state: window::WindowState,
 }
 
@@ -167,6 +168,7 @@ enum DeferredOp {
     ReleaseMouseCapture,
     SetRegion(Option<Region>),
     SetAlwaysOnTop(bool),
+    SetContentProtected(bool),
 }
 
 #[derive(Clone, Debug)]
@@ -427,6 +429,30 @@ fn set_style(hwnd: HWND, resizable: bool, titlebar: bool) {
     }
 }
 
+/// Exclude the window from screenshots and screen capture, or allow it again.
+fn set_display_affinity(hwnd: HWND, protected: bool) {
+    // Not in winapi; available since Windows 10 version 2004. Older versions
+    // reject it, so fall back to showing the window as black in captures.
+    const WDA_EXCLUDEFROMCAPTURE: DWORD = 0x11;
+    unsafe {
+        if !protected {
+            if SetWindowDisplayAffinity(hwnd, WDA_NONE) == 0 {
+                warn!(
+                    "failed to clear window display affinity: {}",
+                    Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+                );
+            }
+        } else if SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE) == 0
+            && SetWindowDisplayAffinity(hwnd, WDA_MONITOR) == 0
+        {
+            warn!(
+                "failed to set window display affinity: {}",
+                Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+            );
+        }
+    }
+}
+
 /// The ex style is different from the non-ex styles.
 fn set_ex_style(hwnd: HWND, always_on_top: bool) {
     unsafe {
@@ -658,6 +684,9 @@ impl MyWndProc {
                     self.with_window_state(|s| s.is_always_on_top.set(always_on_top));
                     set_ex_style(hwnd, always_on_top);
                 }
+                DeferredOp::SetContentProtected(protected) => {
+                    set_display_affinity(hwnd, protected);
+                }
                 DeferredOp::SetWindowState(val) => {
                     let show = if self.handle.borrow().is_focusable() {
                         match val {
@@ -1501,6 +1530,7 @@ impl WindowBuilder {
             position: None,
             level: None,
             always_on_top: false,
+            content_protected: false,
             state: window::WindowState::Restored,
         }
     }
@@ -1530,6 +1560,10 @@ impl WindowBuilder {
         self.always_on_top = always_on_top;
     }
 
+    pub fn set_content_protected(&mut self, protected: bool) {
+        self.content_protected = protected;
+    }
+
     pub fn set_transparent(&mut self, transparent: bool) {
         // Transparency and Flip is only supported on Windows 8 and newer and
         // require DComposition
@@ -1760,6 +1794,10 @@ impl WindowBuilder {
                 std::mem::size_of::<BOOL>() as u32,
             );
 
+            if self.content_protected {
+                set_display_affinity(hwnd, true);
+            }
+
             self.app.add_window(hwnd);
 
             if let Some(accels) = accels {
@@ -2044,6 +2082,7 @@ impl Cursor {
             Cursor::NotAllowed => IDC_NO,
             Cursor::ResizeLeftRight => IDC_SIZEWE,
             Cursor::ResizeUpDown => IDC_SIZENS,
+            Cursor::Wait => IDC_WAIT,
             Cursor::Custom(c) => {
                 return (c.0).0;
             }
@@ -2263,6 +2302,10 @@ impl WindowHandle {
         self.defer(DeferredOp::SetAlwaysOnTop(always_on_top));
     }
 
+    pub fn set_content_protected(&self, protected: bool) {
+        self.defer(DeferredOp::SetContentProtected(protected));
+    }
+
     pub fn resizable(&self, resizable: bool) {
         self.defer(DeferredOp::SetResizable(resizable));
     }
@@ -199,6 +199,7 @@ pub(crate) struct Cursors {
     pub not_allowed: Option<xproto::Cursor>,
     pub row_resize: Option<xproto::Cursor>,
     pub col_resize: Option<xproto::Cursor>,
+    pub wait: Option<xproto::Cursor>,
 }
 
 impl Application {
@@ -297,6 +298,7 @@ impl Application {
             not_allowed: load_cursor("not-allowed"),
             row_resize: load_cursor("row-resize"),
             col_resize: load_cursor("col-resize"),
+            wait: load_cursor("wait"),
         };
 
         let atoms = Rc::new(
@@ -179,6 +179,12 @@ impl WindowBuilder {
         self.always_on_top = always_on_top;
     }
 
+    pub fn set_content_protected(&mut self, protected: bool) {
+        if protected {
+            warn!("WindowBuilder::set_content_protected is unimplemented for X11 backend.");
+        }
+    }
+
     pub fn set_level(&mut self, level: window::WindowLevel) {
         self.level = level;
     }
@@ -1059,6 +1065,7 @@ impl Window {
             Cursor::NotAllowed => cursors.not_allowed,
             Cursor::ResizeLeftRight => cursors.col_resize,
             Cursor::ResizeUpDown => cursors.row_resize,
+            Cursor::Wait => cursors.wait,
             Cursor::Custom(custom) => Some(custom.0),
         };
         if cursor.is_none() {
@@ -1686,6 +1693,11 @@ impl WindowHandle {
         }
     }
 
+    pub fn set_content_protected(&self, _protected: bool) {
+        // X11 has no way to keep other clients from reading the window contents.
+        warn!("WindowHandle::set_content_protected is unimplemented for X11 backend.");
+    }
+
     pub fn set_input_region(&self, region: Option<Region>) {
         if let Some(w) = self.window.upgrade() {
             w.set_input_region(region);
@@ -34,8 +34,18 @@ const MULTI_CLICK_MAX_DISTANCE: f64 = 5.0;
 /// Changes "E&xit" to "Exit". Actual ampersands are escaped as "&&".
 #[allow(dead_code)]
 pub fn strip_access_key(raw_menu_text: &str) -> String {
+    split_access_key(raw_menu_text).0
+}
+
+/// Strip the access keys from the menu string, and find the first one.
+///
+/// Returns the stripped string and the byte offset of the access key in it,
+/// so "E&xit" becomes "Exit" and `Some(1)`.
+#[allow(dead_code)]
+pub fn split_access_key(raw_menu_text: &str) -> (String, Option<usize>) {
     let mut saw_ampersand = false;
     let mut result = String::new();
+    let mut access_key = None;
     for c in raw_menu_text.chars() {
         if c == '&' {
             if saw_ampersand {
@@ -43,11 +53,14 @@ pub fn strip_access_key(raw_menu_text: &str) -> String {
             }
             saw_ampersand = !saw_ampersand;
         } else {
+            if saw_ampersand && access_key.is_none() {
+                access_key = Some(result.len());
+            }
             result.push(c);
             saw_ampersand = false;
         }
     }
-    result
+    (result, access_key)
 }
 
 /// A trait for implementing the boxed callback hack.
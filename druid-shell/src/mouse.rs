@@ -56,6 +56,27 @@ pub struct MouseEvent {
     pub wheel_delta: Vec2,
 }
 
+impl MouseEvent {
+    /// Returns `true` if this event should open a context menu, by the
+    /// conventions of the current platform.
+    ///
+    /// This is a press or release of the right mouse button, or on macOS, of
+    /// the left button while control is held.
+    pub fn is_context_click(&self) -> bool {
+        self.button.is_right()
+            || (cfg!(target_os = "macos") && self.button.is_left() && self.mods.ctrl())
+    }
+
+    /// Returns `true` if this is a press or release of the middle mouse button.
+    ///
+    /// Pressing the scroll wheel is reported as a middle click on all
+    /// platforms. Trackpads on macOS have no way to middle click, so anything
+    /// done on a middle click should also be reachable some other way there.
+    pub fn is_middle_click(&self) -> bool {
+        self.button.is_middle()
+    }
+}
+
 /// An indicator of which mouse button was pressed.
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[repr(u8)]
@@ -264,6 +285,11 @@ pub enum Cursor {
     NotAllowed,
     ResizeLeftRight,
     ResizeUpDown,
+    /// The app is busy and does not respond to input.
+    ///
+    /// macOS has no public busy cursor, so there this falls back to the arrow
+    /// cursor; the system shows its own busy cursor when an app stops responding.
+    Wait,
     // The platform cursor should be small. Any image data that it uses should be shared (i.e.
     // behind an `Arc` or using a platform API that does the sharing).
     Custom(backend::window::CustomCursor),
@@ -305,6 +331,7 @@ impl std::fmt::Debug for Cursor {
             Cursor::NotAllowed => write!(f, "Cursor::NotAllowed"),
             Cursor::ResizeLeftRight => write!(f, "Cursor::ResizeLeftRight"),
             Cursor::ResizeUpDown => write!(f, "Cursor::ResizeUpDown"),
+            Cursor::Wait => write!(f, "Cursor::Wait"),
             Cursor::Custom(_) => write!(f, "Cursor::Custom"),
         }
     }
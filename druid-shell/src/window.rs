@@ -236,6 +236,17 @@ impl WindowHandle {
         self.0.set_always_on_top(always_on_top);
     }
 
+    /// Sets whether the window contents are protected from capture.
+    ///
+    /// A protected window is left out of screenshots, screen recordings and screen
+    /// sharing, which is useful for windows showing sensitive data.
+    ///
+    /// This is currently only implemented on Windows and macOS. On Windows versions
+    /// older than Windows 10 version 2004 the window is captured as a black area.
+    pub fn set_content_protected(&self, protected: bool) {
+        self.0.set_content_protected(protected);
+    }
+
     /// Sets where in the window the user can interact with the program.
     ///
     /// This enables irregularly shaped windows. For example, you can make it simply
@@ -514,6 +525,13 @@ impl WindowBuilder {
         self.0.set_always_on_top(always_on_top);
     }
 
+    /// Set whether the window contents should be protected from capture.
+    ///
+    /// See [`WindowHandle::set_content_protected`] for details.
+    pub fn set_content_protected(&mut self, protected: bool) {
+        self.0.set_content_protected(protected);
+    }
+
     /// Set whether the window background should be transparent
     pub fn set_transparent(&mut self, transparent: bool) {
         self.0.set_transparent(transparent)
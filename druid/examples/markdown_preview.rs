@@ -17,15 +17,11 @@
 // On Windows platform, don't show a console when opening the app.
 #![windows_subsystem = "windows"]
 
-use pulldown_cmark::{Event as ParseEvent, Options, Parser, Tag};
-
-use druid::text::{AttributesAdder, RichText, RichTextBuilder};
 use druid::widget::prelude::*;
-use druid::widget::{Controller, LineBreaking, RawLabel, Scroll, Split, TextBox};
+use druid::widget::{Markdown, Scroll, Split, TextBox};
 use druid::{
-    AppDelegate, AppLauncher, Color, Command, Data, DelegateCtx, FontFamily, FontStyle, FontWeight,
-    Handled, Lens, LocalizedString, Menu, Selector, Target, Widget, WidgetExt, WindowDesc,
-    WindowId,
+    theme, AppDelegate, AppLauncher, Color, Command, Data, DelegateCtx, Handled, Lens,
+    LocalizedString, Menu, Target, Widget, WidgetExt, WindowDesc, WindowId,
 };
 
 const WINDOW_TITLE: LocalizedString<AppState> = LocalizedString::new("Minimal Markdown");
@@ -39,34 +35,11 @@ const TEXT: &str = "*Hello* ***world***! This is a `TextBox` where you can \
 		    [Zulip chat instance]: https://xi.zulipchat.com";
 
 const SPACER_SIZE: f64 = 8.0;
-const BLOCKQUOTE_COLOR: Color = Color::grey8(0x88);
 const LINK_COLOR: Color = Color::rgb8(0, 0, 0xEE);
-const OPEN_LINK: Selector<String> = Selector::new("druid-example.open-link");
 
 #[derive(Clone, Data, Lens)]
 struct AppState {
     raw: String,
-    rendered: RichText,
-}
-
-/// A controller that rebuilds the preview when edits occur
-struct RichTextRebuilder;
-
-impl<W: Widget<AppState>> Controller<AppState, W> for RichTextRebuilder {
-    fn event(
-        &mut self,
-        child: &mut W,
-        ctx: &mut EventCtx,
-        event: &Event,
-        data: &mut AppState,
-        env: &Env,
-    ) {
-        let pre_data = data.raw.to_owned();
-        child.event(ctx, event, data, env);
-        if !data.raw.same(&pre_data) {
-            data.rendered = rebuild_rendered_text(&data.raw);
-        }
-    }
 }
 
 struct Delegate;
@@ -80,7 +53,7 @@ impl<T: Data> AppDelegate<T> for Delegate {
         _data: &mut T,
         _env: &Env,
     ) -> Handled {
-        if let Some(url) = cmd.get(OPEN_LINK) {
+        if let Some(url) = cmd.get(Markdown::OPEN_LINK) {
             #[cfg(not(target_arch = "wasm32"))]
             open::that_in_background(url);
             #[cfg(target_arch = "wasm32")]
@@ -101,7 +74,6 @@ pub fn main() {
     // create the initial app state
     let initial_state = AppState {
         raw: TEXT.to_owned(),
-        rendered: rebuild_rendered_text(TEXT),
     };
 
     // start the application
@@ -113,123 +85,27 @@ pub fn main() {
 }
 
 fn build_root_widget() -> impl Widget<AppState> {
-    let label = Scroll::new(
-        RawLabel::new()
-            .with_text_color(Color::BLACK)
-            .with_line_break_mode(LineBreaking::WordWrap)
-            .lens(AppState::rendered)
+    // The preview is rendered again whenever the text box edits the source.
+    let preview = Scroll::new(
+        Markdown::new()
+            .lens(AppState::raw)
             .expand_width()
             .padding((SPACER_SIZE * 4.0, SPACER_SIZE)),
     )
     .vertical()
+    .env_scope(|env, _| {
+        env.set(theme::TEXT_COLOR, Color::BLACK);
+        env.set(theme::PRIMARY_LIGHT, LINK_COLOR);
+    })
     .background(Color::grey8(222))
     .expand();
 
     let textbox = TextBox::multiline()
         .lens(AppState::raw)
-        .controller(RichTextRebuilder)
         .expand()
         .padding(5.0);
 
-    Split::columns(label, textbox)
-}
-
-/// Parse a markdown string and generate a `RichText` object with
-/// the appropriate attributes.
-fn rebuild_rendered_text(text: &str) -> RichText {
-    let mut current_pos = 0;
-    let mut builder = RichTextBuilder::new();
-    let mut tag_stack = Vec::new();
-
-    let parser = Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH);
-    for event in parser {
-        match event {
-            ParseEvent::Start(tag) => {
-                tag_stack.push((current_pos, tag));
-            }
-            ParseEvent::Text(txt) => {
-                builder.push(&txt);
-                current_pos += txt.len();
-            }
-            ParseEvent::End(end_tag) => {
-                let (start_off, tag) = tag_stack
-                    .pop()
-                    .expect("parser does not return unbalanced tags");
-                assert_eq!(end_tag, tag, "mismatched tags?");
-                add_attribute_for_tag(
-                    &tag,
-                    builder.add_attributes_for_range(start_off..current_pos),
-                );
-                if add_newline_after_tag(&tag) {
-                    builder.push("\n\n");
-                    current_pos += 2;
-                }
-            }
-            ParseEvent::Code(txt) => {
-                builder.push(&txt).font_family(FontFamily::MONOSPACE);
-                current_pos += txt.len();
-            }
-            ParseEvent::Html(txt) => {
-                builder
-                    .push(&txt)
-                    .font_family(FontFamily::MONOSPACE)
-                    .text_color(BLOCKQUOTE_COLOR);
-                current_pos += txt.len();
-            }
-            ParseEvent::HardBreak => {
-                builder.push("\n\n");
-                current_pos += 2;
-            }
-            _ => (),
-        }
-    }
-    builder.build()
-}
-
-fn add_newline_after_tag(tag: &Tag) -> bool {
-    !matches!(
-        tag,
-        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link(..)
-    )
-}
-
-fn add_attribute_for_tag(tag: &Tag, mut attrs: AttributesAdder) {
-    match tag {
-        Tag::Heading(lvl) => {
-            let font_size = match lvl {
-                1 => 38.,
-                2 => 32.0,
-                3 => 26.0,
-                4 => 20.0,
-                5 => 16.0,
-                _ => 12.0,
-            };
-            attrs.size(font_size).weight(FontWeight::BOLD);
-        }
-        Tag::BlockQuote => {
-            attrs.style(FontStyle::Italic).text_color(BLOCKQUOTE_COLOR);
-        }
-        Tag::CodeBlock(_) => {
-            attrs.font_family(FontFamily::MONOSPACE);
-        }
-        Tag::Emphasis => {
-            attrs.style(FontStyle::Italic);
-        }
-        Tag::Strong => {
-            attrs.weight(FontWeight::BOLD);
-        }
-        Tag::Strikethrough => {
-            attrs.strikethrough(true);
-        }
-        Tag::Link(_link_ty, target, _title) => {
-            attrs
-                .underline(true)
-                .text_color(LINK_COLOR)
-                .link(OPEN_LINK.with(target.to_string()));
-        }
-        // ignore other tags for now
-        _ => (),
-    }
+    Split::columns(preview, textbox)
 }
 
 #[allow(unused_assignments, unused_mut)]
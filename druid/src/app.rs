@@ -64,6 +64,7 @@ pub struct WindowConfig {
     pub(crate) show_titlebar: Option<bool>,
     pub(crate) level: Option<WindowLevel>,
     pub(crate) always_on_top: Option<bool>,
+    pub(crate) content_protected: Option<bool>,
     pub(crate) state: Option<WindowState>,
 }
 
@@ -294,6 +295,7 @@ impl Default for WindowConfig {
             transparent: None,
             level: None,
             always_on_top: None,
+            content_protected: None,
             state: None,
         }
     }
@@ -387,6 +389,14 @@ impl WindowConfig {
         self
     }
 
+    /// Sets whether the window contents are protected from capture.
+    ///
+    /// See [`WindowHandle::set_content_protected`] for details.
+    pub fn set_content_protected(mut self, protected: bool) -> Self {
+        self.content_protected = Some(protected);
+        self
+    }
+
     /// Sets the [`WindowState`] of the window.
     pub fn set_window_state(mut self, state: WindowState) -> Self {
         self.state = Some(state);
@@ -431,6 +441,10 @@ impl WindowConfig {
             builder.set_always_on_top(always_on_top)
         }
 
+        if let Some(protected) = self.content_protected {
+            builder.set_content_protected(protected);
+        }
+
         if let Some(state) = self.state {
             builder.set_window_state(state);
         }
@@ -468,6 +482,10 @@ impl WindowConfig {
         if let Some(state) = self.state {
             win_handle.set_window_state(state);
         }
+
+        if let Some(protected) = self.content_protected {
+            win_handle.set_content_protected(protected);
+        }
     }
 }
 
@@ -604,6 +622,17 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Sets whether the window contents are protected from capture.
+    ///
+    /// Windows showing sensitive data, such as passwords or medical records, can use
+    /// this to keep out of screenshots and screen sharing. The protection can be
+    /// toggled later with [`WindowHandle::set_content_protected`], reachable through
+    /// `ctx.window()`.
+    pub fn set_content_protected(mut self, protected: bool) -> Self {
+        self.config = self.config.set_content_protected(protected);
+        self
+    }
+
     /// Set initial state for the window.
     pub fn set_window_state(mut self, state: WindowState) -> Self {
         self.config = self.config.set_window_state(state);
@@ -23,6 +23,9 @@ use std::{
 };
 use tracing::{error, trace, warn};
 
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+
 use crate::commands::SCROLL_TO_VIEW;
 use crate::core::{CommandQueue, CursorChange, FocusChange, WidgetState};
 use crate::env::KeyLike;
@@ -31,6 +34,7 @@ use crate::piet::{Piet, PietText, RenderContext};
 use crate::shell::text::Event as ImeInvalidation;
 use crate::shell::Region;
 use crate::text::{ImeHandlerRef, TextFieldRegistration};
+use crate::window::{PaintBudget, SimulatedTimers};
 use crate::{
     commands, sub_window::SubWindowDesc, widget::Widget, Affine, Command, Cursor, Data, Env,
     ExtEventSink, Insets, Menu, Notification, Point, Rect, Scale, SingleUse, Size, Target,
@@ -73,9 +77,21 @@ pub(crate) struct ContextState<'a> {
     pub(crate) focus_widget: Option<WidgetId>,
     pub(crate) root_app_data_type: TypeId,
     pub(crate) timers: &'a mut HashMap<TimerToken, WidgetId>,
+    /// When set, timers are scheduled on this simulated clock instead of the platform.
+    pub(crate) simulated_timers: Option<&'a mut SimulatedTimers>,
+    /// The budget for the frame being painted, only set while painting.
+    pub(crate) paint_budget: Option<PaintBudget>,
     pub(crate) text_registrations: &'a mut Vec<TextFieldRegistration>,
 }
 
+/// A guard that shows the busy cursor in a window until it is dropped.
+///
+/// This is created with [`EventCtx::busy_cursor`].
+#[must_use = "the busy cursor is removed when the guard is dropped"]
+pub struct BusyCursor {
+    window: WindowHandle,
+}
+
 /// A mutable context provided to event handling methods of widgets.
 ///
 /// Widgets should call [`request_paint`] whenever an event causes a change
@@ -712,6 +728,17 @@ impl_context_method!(
             trace!("request_timer deadline={:?}", deadline);
             self.state.request_timer(self.widget_state.id, deadline)
         }
+
+        /// The current time, for measuring how long passed between timers.
+        ///
+        /// This is the simulated clock when there is one, so that tests don't
+        /// depend on how fast they run.
+        pub(crate) fn now(&self) -> Instant {
+            match self.state.simulated_timers.as_deref() {
+                Some(timers) => timers.instant(),
+                None => Instant::now(),
+            }
+        }
     }
 );
 
@@ -769,6 +796,27 @@ impl EventCtx<'_, '_> {
         // TODO: plumb mouse grab through to platform (through druid-shell)
     }
 
+    /// Show the busy cursor in this window until the returned guard is dropped.
+    ///
+    /// This is meant for work that runs synchronously in an event handler, during
+    /// which the window can't respond to input:
+    ///
+    /// ```ignore
+    /// let _busy = ctx.busy_cursor();
+    /// data.index = build_index(&data.files);
+    /// ```
+    ///
+    /// When the guard is dropped the arrow cursor is restored, and the cursor of
+    /// the widget under the pointer takes over again after the event.
+    /// Work that runs for longer should be moved to another thread instead, and
+    /// report back with an [`ExtEventSink`].
+    pub fn busy_cursor(&self) -> BusyCursor {
+        trace!("busy_cursor");
+        let mut window = self.state.window.clone();
+        window.set_cursor(&Cursor::Wait);
+        BusyCursor { window }
+    }
+
     /// Create a new window.
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
     ///
@@ -847,6 +895,23 @@ impl EventCtx<'_, '_> {
         self.widget_state.request_focus = Some(FocusChange::Focus(target));
     }
 
+    /// The widget that has keyboard focus in this window, if any.
+    pub(crate) fn focused_widget(&self) -> Option<WidgetId> {
+        self.state.focus_widget
+    }
+
+    /// Take a pending request from a descendant to move focus along the focus
+    /// chain, returning `true` if it was towards the next widget.
+    pub(crate) fn take_focus_traversal(&mut self) -> Option<bool> {
+        let forward = match self.widget_state.request_focus {
+            Some(FocusChange::Next) => true,
+            Some(FocusChange::Previous) => false,
+            _ => return None,
+        };
+        self.widget_state.request_focus = None;
+        Some(forward)
+    }
+
     /// Transfer focus to the next focusable widget.
     ///
     /// This should only be called by a widget that currently has focus.
@@ -1018,6 +1083,15 @@ impl LifeCycleCtx<'_, '_> {
         self.widget_state.focus_chain.push(self.widget_id());
     }
 
+    /// The widgets that registered for focus so far during
+    /// [`LifeCycle::BuildFocusChain`], including this widget's descendants
+    /// once they have handled it.
+    ///
+    /// [`LifeCycle::BuildFocusChain`]: crate::LifeCycle::BuildFocusChain
+    pub(crate) fn focus_chain(&self) -> &[WidgetId] {
+        &self.widget_state.focus_chain
+    }
+
     /// Register this widget as accepting text input.
     pub fn register_text_input(&mut self, document: impl ImeHandlerRef + 'static) {
         let registration = TextFieldRegistration {
@@ -1100,6 +1174,64 @@ impl PaintCtx<'_, '_, '_> {
         &self.region
     }
 
+    /// The time left for painting before the current budget runs out.
+    ///
+    /// This is the rest of the window's frame budget, or less inside
+    /// [`with_budget`]. Widgets with expensive painting can use this to decide
+    /// how much detail to draw. See also [`is_under_load`].
+    ///
+    /// [`with_budget`]: PaintCtx::with_budget
+    /// [`is_under_load`]: PaintCtx::is_under_load
+    pub fn remaining_budget(&self) -> Duration {
+        match &self.state.paint_budget {
+            Some(budget) => {
+                let now = budget.now(self.state.simulated_timers.as_deref());
+                budget.deadline.saturating_sub(now)
+            }
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Returns `true` if painting should be made cheaper to keep the window
+    /// responsive.
+    ///
+    /// This is the case when the previous frame of the window took longer to
+    /// paint than its budget, or when the current budget has run out. Widgets
+    /// can check this to skip effects such as shadows, or to draw with less
+    /// detail, until the load drops again.
+    ///
+    /// Whether the previous frame was too slow is tracked for the whole window,
+    /// not per widget; use [`with_budget`] to give a part of the tree a budget
+    /// of its own within the current frame.
+    ///
+    /// [`with_budget`]: PaintCtx::with_budget
+    pub fn is_under_load(&self) -> bool {
+        match &self.state.paint_budget {
+            Some(budget) => budget.over_budget || self.remaining_budget() == Duration::ZERO,
+            None => false,
+        }
+    }
+
+    /// Paint with at most `budget` of the remaining frame budget.
+    ///
+    /// Containers can use this to limit how much of the frame a single child
+    /// may use: inside `f`, [`remaining_budget`] and [`is_under_load`] are
+    /// measured against the smaller budget. This does not interrupt painting;
+    /// it is up to the widgets to check the budget.
+    ///
+    /// [`remaining_budget`]: PaintCtx::remaining_budget
+    /// [`is_under_load`]: PaintCtx::is_under_load
+    pub fn with_budget(&mut self, budget: Duration, f: impl FnOnce(&mut PaintCtx)) {
+        let outer = self.state.paint_budget;
+        if let Some(mut inner) = outer {
+            let now = inner.now(self.state.simulated_timers.as_deref());
+            inner.deadline = inner.deadline.min(now + budget);
+            self.state.paint_budget = Some(inner);
+        }
+        f(self);
+        self.state.paint_budget = outer;
+    }
+
     /// Creates a temporary `PaintCtx` with a new visible region, and calls
     /// the provided function with that `PaintCtx`.
     ///
@@ -1186,6 +1318,8 @@ impl<'a> ContextState<'a> {
             window_id,
             focus_widget,
             timers,
+            simulated_timers: None,
+            paint_budget: None,
             text_registrations,
             text: window.text(),
             root_app_data_type: TypeId::of::<T>(),
@@ -1200,12 +1334,21 @@ impl<'a> ContextState<'a> {
 
     fn request_timer(&mut self, widget_id: WidgetId, deadline: Duration) -> TimerToken {
         trace!("request_timer deadline={:?}", deadline);
-        let timer_token = self.window.request_timer(deadline);
+        let timer_token = match self.simulated_timers.as_mut() {
+            Some(simulated) => simulated.request_timer(deadline),
+            None => self.window.request_timer(deadline),
+        };
         self.timers.insert(timer_token, widget_id);
         timer_token
     }
 }
 
+impl Drop for BusyCursor {
+    fn drop(&mut self) {
+        self.window.set_cursor(&Cursor::Arrow);
+    }
+}
+
 impl<'c> Deref for PaintCtx<'_, '_, 'c> {
     type Target = Piet<'c>;
 
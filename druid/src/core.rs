@@ -1224,6 +1224,14 @@ impl<T, W> WidgetPod<T, W> {
     pub fn widget_mut(&mut self) -> &mut W {
         &mut self.inner
     }
+
+    /// Take the inner widget out, dropping the pod's state.
+    ///
+    /// The widget can be put in a new pod, where it is added to the tree again
+    /// with a new id and no hot, active or focus state.
+    pub(crate) fn into_inner(self) -> W {
+        self.inner
+    }
 }
 
 impl WidgetState {
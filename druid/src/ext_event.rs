@@ -114,6 +114,48 @@ impl ExtEventSink {
         Ok(())
     }
 
+    /// Submit a [`Command`], replacing any command with the same [`Selector`]
+    /// and [`Target`] that has been submitted but not yet received.
+    ///
+    /// This is intended for chatty producers such as progress reports or sensor
+    /// readings, where only the latest value matters: however quickly commands
+    /// are submitted, at most one per selector and target is ever queued.
+    ///
+    /// A replaced command keeps its position in the queue.
+    pub fn submit_command_coalesced<T: Any + Send>(
+        &self,
+        selector: Selector<T>,
+        payload: impl Into<Box<T>>,
+        target: impl Into<Target>,
+    ) -> Result<(), ExtEventError> {
+        let target = target.into();
+        let payload: Box<T> = payload.into();
+        let symbol = selector.symbol();
+        let mut queue = self.queue.lock().map_err(|_| ExtEventError)?;
+        if let Some(pending) = queue
+            .iter_mut()
+            .find(|(s, _, t)| *s == symbol && *t == target)
+        {
+            // The idle callback for the pending command has already been scheduled.
+            pending.1 = payload;
+            return Ok(());
+        }
+        queue.push_back((symbol, payload, target));
+        drop(queue);
+        if let Some(handle) = self.handle.lock().unwrap().as_mut() {
+            handle.schedule_idle(EXT_EVENT_IDLE_TOKEN);
+        }
+        Ok(())
+    }
+
+    /// The number of submitted commands that the application has not received yet.
+    ///
+    /// Producers can use this for backpressure, for instance by pausing work
+    /// while the count is above some threshold.
+    pub fn pending_commands(&self) -> usize {
+        self.queue.lock().map(|queue| queue.len()).unwrap_or(0)
+    }
+
     /// Schedule an idle callback.
     ///
     /// `T` must be the application's root `Data` type (the type provided to [`AppLauncher::launch`]).
@@ -149,3 +191,30 @@ impl std::fmt::Display for ExtEventError {
 }
 
 impl std::error::Error for ExtEventError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    const PROGRESS: Selector<f64> = Selector::new("druid-test.ext-progress");
+    const OTHER: Selector<f64> = Selector::new("druid-test.ext-other");
+
+    #[test]
+    fn coalesced_commands_replace_pending() {
+        let mut host = ExtEventHost::new();
+        let sink = host.make_sink();
+        sink.submit_command_coalesced(PROGRESS, 0.1, Target::Global)
+            .unwrap();
+        sink.submit_command(OTHER, 1.0, Target::Global).unwrap();
+        sink.submit_command_coalesced(PROGRESS, 0.5, Target::Global)
+            .unwrap();
+        assert_eq!(sink.pending_commands(), 2);
+
+        let first = host.recv().unwrap();
+        assert_eq!(first.get(PROGRESS), Some(&0.5));
+        assert!(host.recv().unwrap().is(OTHER));
+        assert!(host.recv().is_none());
+        assert_eq!(sink.pending_commands(), 0);
+    }
+}
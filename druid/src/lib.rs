@@ -99,6 +99,7 @@
 //!          which is made available via the [`im` module].
 //! * `svg` - Scalable Vector Graphics for icons and other scalable images using the [`usvg` crate].
 //! * `image` - Bitmap image support using the [`image` crate].
+//! * `markdown` - A [`Markdown`] widget using the [`pulldown-cmark` crate].
 //! * `x11` - Work-in-progress X11 backend instead of GTK.
 //! * `wayland` - Work-in-progress Wayland backend, very experimental.
 //! * `serde` - Serde support for some internal types (most Kurbo primitives).
@@ -142,6 +143,8 @@
 //! [`im` module]: im/index.html
 //! [`usvg` crate]: https://crates.io/crates/usvg
 //! [`image` crate]: https://crates.io/crates/image
+//! [`Markdown`]: widget/struct.Markdown.html
+//! [`pulldown-cmark` crate]: https://crates.io/crates/pulldown-cmark
 
 #![deny(
     rustdoc::broken_intra_doc_links,
@@ -169,6 +172,11 @@ pub use druid_shell::{kurbo, piet};
 #[doc(inline)]
 pub use im;
 
+// the markdown widget accepts events from the pulldown-cmark parser
+#[cfg(feature = "markdown")]
+#[doc(inline)]
+pub use pulldown_cmark;
+
 #[macro_use]
 pub mod lens;
 
@@ -192,6 +200,8 @@ mod localization;
 pub mod menu;
 mod mouse;
 pub mod scroll_component;
+mod spatial_index;
+mod state_machine;
 mod sub_window;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tests;
@@ -224,7 +234,7 @@ pub use app::{AppLauncher, WindowConfig, WindowDesc, WindowSizePolicy};
 pub use app_delegate::{AppDelegate, DelegateCtx};
 pub use box_constraints::BoxConstraints;
 pub use command::{sys as commands, Command, Notification, Selector, SingleUse, Target};
-pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx};
+pub use contexts::{BusyCursor, ChangeCtx, EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, UpdateCtx};
 pub use data::*; // Wildcard because rustdoc has trouble inlining docs of two things called Data
 pub use dialog::FileDialogOptions;
 #[doc(inline)]
@@ -236,6 +246,8 @@ pub use localization::LocalizedString;
 #[doc(inline)]
 pub use menu::{sys as platform_menus, Menu, MenuItem};
 pub use mouse::MouseEvent;
+pub use spatial_index::SpatialIndex;
+pub use state_machine::{StateMachine, Transition};
 pub use util::Handled;
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
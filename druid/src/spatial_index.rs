@@ -0,0 +1,225 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A uniform grid for hit testing large numbers of shapes.
+
+use std::collections::HashMap;
+
+use crate::kurbo::{Point, Rect};
+
+/// Shapes that would be recorded in more cells than this, including shapes
+/// with infinite or NaN bounds, are kept in a list that every lookup checks.
+const MAX_CELLS_PER_SHAPE: i128 = 256;
+
+/// A spatial index for hit testing many shapes.
+///
+/// Widgets that draw a lot of primitives, such as charts, maps or node graphs,
+/// can insert the bounding box of each primitive along with a key, and then ask
+/// which keys are under the pointer without checking every shape.
+///
+/// The index is a uniform grid of square cells, and each shape is recorded in
+/// every cell its bounding box overlaps. A cell size close to the size of a
+/// typical shape works well; shapes that span a lot of cells are checked on
+/// every lookup instead.
+///
+/// Only bounding boxes are stored; widgets with non-rectangular shapes should
+/// do a precise test on the candidates that are returned.
+pub struct SpatialIndex<K> {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    /// Shapes too large to be recorded in cells.
+    large: Vec<usize>,
+    entries: Vec<(K, Rect)>,
+}
+
+impl<K: Copy> SpatialIndex<K> {
+    /// Create an empty index with cells of `cell_size` by `cell_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not a positive number.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "cell_size must be positive");
+        SpatialIndex {
+            cell_size,
+            cells: HashMap::new(),
+            large: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a shape with the given `key` and bounding box.
+    ///
+    /// Shapes inserted later are considered to be drawn on top of earlier ones.
+    pub fn insert(&mut self, key: K, bounds: Rect) {
+        let bounds = bounds.abs();
+        let idx = self.entries.len();
+        self.entries.push((key, bounds));
+        let (x0, y0, x1, y1) = self.cell_range(bounds);
+        if !bounds.is_finite() || cell_count(x0, y0, x1, y1) > MAX_CELLS_PER_SHAPE {
+            self.large.push(idx);
+            return;
+        }
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(idx);
+            }
+        }
+    }
+
+    /// Remove all shapes.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.large.clear();
+        self.cells.clear();
+    }
+
+    /// The number of shapes in the index.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the index contains no shapes.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The keys of all shapes whose bounds contain `point`, topmost first.
+    pub fn hit(&self, point: Point) -> impl Iterator<Item = K> + '_ {
+        let mut ids: Vec<usize> = self
+            .cells
+            .get(&self.cell_of(point))
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .chain(&self.large)
+            .copied()
+            .collect();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        ids.into_iter()
+            .filter(move |&idx| self.entries[idx].1.contains(point))
+            .map(move |idx| self.entries[idx].0)
+    }
+
+    /// The keys of all shapes whose bounds overlap `rect`, in insertion order.
+    pub fn query(&self, rect: Rect) -> Vec<K> {
+        let rect = rect.abs();
+        let (x0, y0, x1, y1) = self.cell_range(rect);
+        let mut ids = self.large.clone();
+        // Large queries look at the cells that have shapes instead of at
+        // every cell they cover, which could be practically endless.
+        if cell_count(x0, y0, x1, y1) <= self.cells.len() as i128 {
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    if let Some(cell) = self.cells.get(&(x, y)) {
+                        ids.extend_from_slice(cell);
+                    }
+                }
+            }
+        } else {
+            for (&(x, y), cell) in &self.cells {
+                if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) {
+                    ids.extend_from_slice(cell);
+                }
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        let overlaps =
+            |b: &Rect| b.x0 < rect.x1 && rect.x0 < b.x1 && b.y0 < rect.y1 && rect.y0 < b.y1;
+        ids.into_iter()
+            .map(|idx| self.entries[idx])
+            .filter(|(_, bounds)| overlaps(bounds))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    fn cell_of(&self, point: Point) -> (i64, i64) {
+        (
+            (point.x / self.cell_size).floor() as i64,
+            (point.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// The first and last cells covered by `rect`, as `(x0, y0, x1, y1)`.
+    fn cell_range(&self, rect: Rect) -> (i64, i64, i64, i64) {
+        let (x0, y0) = self.cell_of(Point::new(rect.x0, rect.y0));
+        let (x1, y1) = self.cell_of(Point::new(rect.x1, rect.y1));
+        (x0, y0, x1, y1)
+    }
+}
+
+/// The number of cells in a range, which can be more than fits in an `i64`.
+fn cell_count(x0: i64, y0: i64, x1: i64, y1: i64) -> i128 {
+    let width = (x1 as i128 - x0 as i128 + 1).max(0);
+    let height = (y1 as i128 - y0 as i128 + 1).max(0);
+    width.saturating_mul(height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn hit_returns_topmost_first() {
+        let mut index = SpatialIndex::new(10.0);
+        index.insert(1, Rect::new(0.0, 0.0, 30.0, 30.0));
+        index.insert(2, Rect::new(5.0, 5.0, 15.0, 15.0));
+        index.insert(3, Rect::new(50.0, 50.0, 60.0, 60.0));
+
+        let hits: Vec<_> = index.hit(Point::new(8.0, 8.0)).collect();
+        assert_eq!(hits, vec![2, 1]);
+        let hits: Vec<_> = index.hit(Point::new(25.0, 25.0)).collect();
+        assert_eq!(hits, vec![1]);
+        assert_eq!(index.hit(Point::new(40.0, 40.0)).next(), None);
+    }
+
+    #[test]
+    fn query_reports_each_key_once() {
+        let mut index = SpatialIndex::new(4.0);
+        index.insert('a', Rect::new(0.0, 0.0, 20.0, 20.0));
+        index.insert('b', Rect::new(-10.0, -10.0, -5.0, -5.0));
+        index.insert('c', Rect::new(30.0, 0.0, 40.0, 10.0));
+
+        assert_eq!(
+            index.query(Rect::new(-8.0, -8.0, 12.0, 12.0)),
+            vec!['a', 'b']
+        );
+        assert_eq!(
+            index.query(Rect::new(20.0, 0.0, 30.0, 10.0)),
+            Vec::<char>::new()
+        );
+
+        index.clear();
+        assert!(index.is_empty());
+        assert!(index
+            .query(Rect::new(-100.0, -100.0, 100.0, 100.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn huge_shapes_and_queries_finish() {
+        let mut index = SpatialIndex::new(1.0);
+        index.insert(1, Rect::new(0.0, 0.0, 2.0, 2.0));
+        index.insert(2, Rect::new(-1e12, -1e12, 1e12, 1e12));
+        index.insert(3, Rect::new(f64::NEG_INFINITY, 0.0, f64::INFINITY, 1.0));
+        index.insert(4, Rect::new(f64::NAN, 0.0, 1.0, 1.0));
+
+        let hits: Vec<_> = index.hit(Point::new(0.5, 0.5)).collect();
+        assert_eq!(hits, vec![3, 2, 1]);
+        let everything = Rect::new(f64::NEG_INFINITY, -1e300, f64::INFINITY, 1e300);
+        assert_eq!(index.query(everything), vec![1, 2, 3]);
+        assert_eq!(index.query(Rect::new(5.0, 5.0, 6.0, 6.0)), vec![2]);
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small state machine for modelling multi-step interactions.
+
+use std::time::Duration;
+
+use tracing::trace;
+
+use crate::{ChangeCtx, EventCtx, TimerToken};
+
+/// A transition predicate; returns `true` if the event should trigger the transition.
+type Guard<E> = Box<dyn Fn(&E) -> bool>;
+/// An action run when a state is entered or exited.
+type Action<S> = Box<dyn Fn(&mut EventCtx, &Transition<S>)>;
+
+/// A small state machine, useful for interactions that span several events.
+///
+/// Complex interactions such as drag-with-modifiers, press-and-hold or multi-step
+/// gestures quickly turn into a tangle of boolean flags on a widget. A `StateMachine`
+/// replaces those flags with an explicit state `S`, driven by events `E` that the
+/// widget derives from the [`Event`]s it receives.
+///
+/// Transitions are checked in the order they were added; the first one whose
+/// source state matches the current state and whose guard accepts the event wins.
+///
+/// A state can have a timeout: when it is entered a timer is requested, and if the
+/// machine is still in that state when the timer fires, the timeout event is sent
+/// to the machine. Forward [`Event::Timer`] tokens to [`StateMachine::timer`] for
+/// this to work.
+///
+/// Entry and exit actions can be attached to states; they are run with the
+/// [`EventCtx`] whenever [`send`] or [`timer`] takes a transition, so they can
+/// request paints, submit commands or start timers of their own. Both methods
+/// also return the [`Transition`] that was taken, if any.
+///
+/// [`Event`]: crate::Event
+/// [`Event::Timer`]: crate::Event::Timer
+/// [`send`]: StateMachine::send
+/// [`timer`]: StateMachine::timer
+pub struct StateMachine<S, E> {
+    state: S,
+    transitions: Vec<(S, Guard<E>, S)>,
+    timeouts: Vec<(S, Duration, E)>,
+    on_enter: Vec<(S, Action<S>)>,
+    on_exit: Vec<(S, Action<S>)>,
+    timer: TimerToken,
+}
+
+/// A change of state in a [`StateMachine`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition<S> {
+    /// The state that was left.
+    pub from: S,
+    /// The state that was entered.
+    pub to: S,
+}
+
+impl<S: Clone + PartialEq, E> StateMachine<S, E> {
+    /// Create a new `StateMachine` in the `initial` state.
+    pub fn new(initial: S) -> Self {
+        StateMachine {
+            state: initial,
+            transitions: Vec::new(),
+            timeouts: Vec::new(),
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+            timer: TimerToken::INVALID,
+        }
+    }
+
+    /// Builder-style method to add a transition from `from` to `to`, taken when
+    /// `guard` returns `true` for an event.
+    pub fn with_transition(mut self, from: S, to: S, guard: impl Fn(&E) -> bool + 'static) -> Self {
+        self.transitions.push((from, Box::new(guard), to));
+        self
+    }
+
+    /// Builder-style method to send `event` to the machine if it stays in `state`
+    /// for longer than `after`.
+    ///
+    /// Only one timeout per state is used; the first one added wins.
+    pub fn with_timeout(mut self, state: S, after: Duration, event: E) -> Self {
+        self.timeouts.push((state, after, event));
+        self
+    }
+
+    /// Builder-style method to run `action` whenever the machine enters `state`.
+    ///
+    /// Entry actions run after the exit actions of the previous state, and
+    /// before the timeout of `state` is armed. Timers requested by an action
+    /// belong to the owner, and are ignored by [`timer`].
+    ///
+    /// [`timer`]: StateMachine::timer
+    pub fn with_on_enter(
+        mut self,
+        state: S,
+        action: impl Fn(&mut EventCtx, &Transition<S>) + 'static,
+    ) -> Self {
+        self.on_enter.push((state, Box::new(action)));
+        self
+    }
+
+    /// Builder-style method to run `action` whenever the machine leaves `state`.
+    pub fn with_on_exit(
+        mut self,
+        state: S,
+        action: impl Fn(&mut EventCtx, &Transition<S>) + 'static,
+    ) -> Self {
+        self.on_exit.push((state, Box::new(action)));
+        self
+    }
+
+    /// The current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns `true` if the machine is currently in `state`.
+    pub fn is_in(&self, state: &S) -> bool {
+        self.state == *state
+    }
+
+    /// Send an event to the machine.
+    ///
+    /// If a transition is taken, the exit and entry actions are run, and if the
+    /// new state has a timeout, a timer is requested on `ctx`.
+    pub fn send(&mut self, ctx: &mut EventCtx, event: &E) -> Option<Transition<S>> {
+        let transition = self.step(event)?;
+        self.run_actions(ctx, &transition);
+        self.arm_timer(ctx);
+        Some(transition)
+    }
+
+    /// Handle a timer token, usually from an [`Event::Timer`].
+    ///
+    /// If the token belongs to the timeout of the current state, the timeout event
+    /// is sent to the machine. Other tokens are ignored and return `None`.
+    ///
+    /// [`Event::Timer`]: crate::Event::Timer
+    pub fn timer(&mut self, ctx: &mut EventCtx, token: TimerToken) -> Option<Transition<S>> {
+        if token == TimerToken::INVALID || token != self.timer {
+            return None;
+        }
+        self.timer = TimerToken::INVALID;
+        let idx = self
+            .timeouts
+            .iter()
+            .position(|(s, _, _)| *s == self.state)?;
+        // The guard closures borrow the event, so take it out while we step.
+        let (state, after, event) = self.timeouts.remove(idx);
+        let transition = self.step(&event);
+        self.timeouts.insert(idx, (state, after, event));
+        let transition = transition?;
+        self.run_actions(ctx, &transition);
+        self.arm_timer(ctx);
+        Some(transition)
+    }
+
+    /// Force the machine into `state`, without consulting any transitions.
+    ///
+    /// Any pending timeout is cancelled, and the timeout of `state` is armed.
+    /// Entry and exit actions are not run.
+    pub fn reset(&mut self, ctx: &mut impl ChangeCtx, state: S) {
+        self.state = state;
+        self.timer = TimerToken::INVALID;
+        self.arm_timer(ctx);
+    }
+
+    fn step(&mut self, event: &E) -> Option<Transition<S>> {
+        let (_, _, to) = self
+            .transitions
+            .iter()
+            .find(|(from, guard, _)| *from == self.state && guard(event))?;
+        let from = std::mem::replace(&mut self.state, to.clone());
+        // Whatever timer was pending belonged to the state we just left.
+        self.timer = TimerToken::INVALID;
+        trace!("state machine transition taken");
+        Some(Transition {
+            from,
+            to: self.state.clone(),
+        })
+    }
+
+    fn run_actions(&self, ctx: &mut EventCtx, transition: &Transition<S>) {
+        for (_, action) in self.on_exit.iter().filter(|(s, _)| transition.exited(s)) {
+            action(ctx, transition);
+        }
+        for (_, action) in self.on_enter.iter().filter(|(s, _)| transition.entered(s)) {
+            action(ctx, transition);
+        }
+    }
+
+    fn arm_timer(&mut self, ctx: &mut impl ChangeCtx) {
+        if let Some((_, after, _)) = self.timeouts.iter().find(|(s, _, _)| *s == self.state) {
+            self.timer = ctx.request_timer(*after);
+        }
+    }
+}
+
+impl<S: PartialEq> Transition<S> {
+    /// Returns `true` if this transition left `state`.
+    ///
+    /// Self-transitions both exit and enter their state.
+    pub fn exited(&self, state: &S) -> bool {
+        self.from == *state
+    }
+
+    /// Returns `true` if this transition entered `state`.
+    pub fn entered(&self, state: &S) -> bool {
+        self.to == *state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::widget::prelude::*;
+    use crate::Selector;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Drag {
+        Idle,
+        Pressed,
+        Dragging,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Input {
+        Down,
+        Move(f64),
+        Up,
+    }
+
+    fn drag_machine() -> StateMachine<Drag, Input> {
+        StateMachine::new(Drag::Idle)
+            .with_transition(Drag::Idle, Drag::Pressed, |e| *e == Input::Down)
+            .with_transition(
+                Drag::Pressed,
+                Drag::Dragging,
+                |e| matches!(e, Input::Move(d) if d.abs() > 4.0),
+            )
+            .with_transition(Drag::Pressed, Drag::Idle, |e| *e == Input::Up)
+            .with_transition(Drag::Dragging, Drag::Idle, |e| *e == Input::Up)
+    }
+
+    #[test]
+    fn transitions_follow_guards() {
+        let mut machine = drag_machine();
+        assert!(machine.step(&Input::Move(10.0)).is_none());
+        assert!(machine.is_in(&Drag::Idle));
+
+        let t = machine.step(&Input::Down).unwrap();
+        assert!(t.exited(&Drag::Idle));
+        assert!(t.entered(&Drag::Pressed));
+
+        assert!(machine.step(&Input::Move(1.0)).is_none());
+        assert_eq!(
+            machine.step(&Input::Move(5.0)),
+            Some(Transition {
+                from: Drag::Pressed,
+                to: Drag::Dragging
+            })
+        );
+        assert!(machine.step(&Input::Down).is_none());
+        assert!(machine.step(&Input::Up).is_some());
+        assert_eq!(*machine.state(), Drag::Idle);
+    }
+
+    #[test]
+    fn first_matching_transition_wins() {
+        let mut machine = StateMachine::new(Drag::Idle)
+            .with_transition(Drag::Idle, Drag::Dragging, |_: &Input| true)
+            .with_transition(Drag::Idle, Drag::Pressed, |_| true);
+        machine.step(&Input::Down);
+        assert!(machine.is_in(&Drag::Dragging));
+    }
+
+    /// Sends `Input`s to the machine of a [`machine_widget`].
+    const SEND: Selector<Input> = Selector::new("druid-tests.state-machine-send");
+
+    /// A widget driving `machine` with [`SEND`] commands, counting timeout
+    /// transitions in its data.
+    fn machine_widget(mut machine: StateMachine<Drag, Input>) -> impl Widget<u32> {
+        ModularWidget::new(())
+            .event_fn(move |_, ctx, event, count: &mut u32, _| match event {
+                Event::Command(cmd) => {
+                    if let Some(input) = cmd.get(SEND) {
+                        machine.send(ctx, input);
+                    }
+                }
+                Event::Timer(token) => {
+                    if machine.timer(ctx, *token).is_some() {
+                        *count += 1;
+                    }
+                }
+                _ => (),
+            })
+            .layout_fn(|_, _, bc, _, _| bc.min())
+    }
+
+    #[test]
+    fn stale_timer_is_ignored() {
+        let machine = drag_machine().with_timeout(
+            Drag::Pressed,
+            Duration::from_millis(500),
+            Input::Move(10.0),
+        );
+
+        Harness::create_simple(0, machine_widget(machine), |harness| {
+            harness.send_initial_events();
+            harness.submit_command(SEND.with(Input::Down));
+            harness.advance(Duration::from_millis(300));
+            harness.submit_command(SEND.with(Input::Up));
+            harness.submit_command(SEND.with(Input::Down));
+            // The timer of the first press fires, but the machine has moved on.
+            harness.advance(Duration::from_millis(250));
+            assert_eq!(*harness.data(), 0);
+            harness.advance(Duration::from_millis(300));
+            assert_eq!(*harness.data(), 1);
+        });
+    }
+
+    #[test]
+    fn actions_run_on_transitions() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let (exit_log, enter_log) = (log.clone(), log.clone());
+        let machine = drag_machine()
+            .with_on_exit(Drag::Idle, move |_, t| {
+                exit_log.borrow_mut().push(("exit", t.to))
+            })
+            .with_on_enter(Drag::Idle, move |_, t| {
+                enter_log.borrow_mut().push(("enter", t.from))
+            });
+
+        Harness::create_simple(0, machine_widget(machine), |harness| {
+            harness.send_initial_events();
+            harness.submit_command(SEND.with(Input::Down));
+            harness.submit_command(SEND.with(Input::Move(10.0)));
+            harness.submit_command(SEND.with(Input::Up));
+        });
+        assert_eq!(
+            *log.borrow(),
+            vec![("exit", Drag::Pressed), ("enter", Drag::Dragging)]
+        );
+    }
+}
@@ -16,17 +16,22 @@
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::app::PendingWindow;
 use crate::core::{CommandQueue, WidgetState};
 use crate::ext_event::ExtEventHost;
 use crate::piet::{BitmapTarget, Device, Error, ImageFormat, Piet};
+use crate::window::SimulatedTimers;
 use crate::*;
 
 use crate::debug_state::DebugState;
 
 pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
 
+/// The interval between animation frames on the simulated clock; see [`Harness::advance`].
+pub const ANIM_FRAME_INTERVAL: Duration = Duration::from_nanos(16_666_667);
+
 /// A type that tries very hard to provide a comforting and safe environment
 /// for widgets who are trying to find their way.
 ///
@@ -46,11 +51,16 @@ pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
 ///
 /// if you want those functions run you will need to call them yourself.
 ///
-/// Also, timers don't work.  ¯\_(ツ)_/¯
+/// Time does not pass on its own either: timers and animation frames are driven
+/// by a simulated clock, which you move forward with [`advance`].
+///
+/// [`advance`]: Harness::advance
 pub struct Harness<'a, T> {
     piet: Piet<'a>,
     mock_app: MockAppState<T>,
     window_size: Size,
+    /// The simulated time of the last animation frame, if we are animating.
+    last_anim_frame: Option<Duration>,
 }
 
 /// All of the state except for the `Piet` (render context). We need to pass
@@ -152,7 +162,8 @@ impl<T: Data> Harness<'_, T> {
             let piet = target.0.as_mut().unwrap().render_context();
 
             let pending = PendingWindow::new(root);
-            let window = Window::new(WindowId::next(), Default::default(), pending, ext_handle);
+            let mut window = Window::new(WindowId::next(), Default::default(), pending, ext_handle);
+            window.simulated_timers = Some(SimulatedTimers::default());
 
             let mock_app = MockAppState {
                 data,
@@ -165,6 +176,7 @@ impl<T: Data> Harness<'_, T> {
                 piet,
                 mock_app,
                 window_size,
+                last_anim_frame: None,
             };
             harness_closure(&mut harness);
         }
@@ -253,6 +265,31 @@ impl<T: Data> Harness<'_, T> {
         self.event(event);
     }
 
+    /// Run `interaction` against this harness and assert that afterwards the
+    /// data is the [`same`] as `expected`.
+    ///
+    /// On failure the panic message shows the data from before the interaction
+    /// alongside the expected and the actual data.
+    ///
+    /// [`same`]: Data::same
+    #[track_caller]
+    pub fn assert_data_after(&mut self, expected: T, interaction: impl FnOnce(&mut Self))
+    where
+        T: std::fmt::Debug,
+    {
+        let before = self.data().clone();
+        interaction(self);
+        let actual = self.data();
+        if !actual.same(&expected) {
+            panic!(
+                "data did not match expectation after interaction:\n  \
+                 before:   {before:?}\n  \
+                 expected: {expected:?}\n  \
+                 actual:   {actual:?}"
+            );
+        }
+    }
+
     /// Send the events that would normally be sent when the app starts.
     // should we do this automatically? Also these will change regularly?
     pub fn send_initial_events(&mut self) {
@@ -291,6 +328,71 @@ impl<T: Data> Harness<'_, T> {
         self.mock_app.update()
     }
 
+    /// The time elapsed on the simulated clock since the harness was created.
+    pub fn elapsed(&self) -> Duration {
+        self.clock().now
+    }
+
+    /// Advance the simulated clock by `duration`.
+    ///
+    /// Timers requested by widgets fire, in order, once their deadline has
+    /// passed. While any widget wants animation frames, an [`Event::AnimFrame`]
+    /// is sent every [`ANIM_FRAME_INTERVAL`]; as in a real window, the first
+    /// frame after an idle period reports an interval of zero.
+    ///
+    /// Layout and paint are still not called automatically.
+    pub fn advance(&mut self, duration: Duration) {
+        let end = self.elapsed() + duration;
+        loop {
+            let timer = self.clock().next_deadline().filter(|t| *t <= end);
+            let frame = if self.mock_app.window.wants_animation_frame() {
+                let next = self
+                    .last_anim_frame
+                    .map_or(self.elapsed(), |last| last + ANIM_FRAME_INTERVAL);
+                Some(next).filter(|t| *t <= end)
+            } else {
+                self.last_anim_frame = None;
+                None
+            };
+
+            match (timer, frame) {
+                (Some(timer), Some(frame)) if frame < timer => self.anim_frame(frame),
+                (Some(_), _) => {
+                    let token = self.clock_mut().pop_next().unwrap();
+                    self.event(Event::Timer(token));
+                }
+                (None, Some(frame)) => self.anim_frame(frame),
+                (None, None) => break,
+            }
+        }
+        self.clock_mut().now = end;
+    }
+
+    fn anim_frame(&mut self, at: Duration) {
+        let interval = self
+            .last_anim_frame
+            .map_or(0, |last| (at - last).as_nanos() as u64);
+        self.clock_mut().now = at;
+        self.last_anim_frame = Some(at);
+        self.event(Event::AnimFrame(interval));
+    }
+
+    fn clock(&self) -> &SimulatedTimers {
+        self.mock_app
+            .window
+            .simulated_timers
+            .as_ref()
+            .expect("harness window always has a simulated clock")
+    }
+
+    fn clock_mut(&mut self) -> &mut SimulatedTimers {
+        self.mock_app
+            .window
+            .simulated_timers
+            .as_mut()
+            .expect("harness window always has a simulated clock")
+    }
+
     /// Only do a layout pass, without painting
     pub fn just_layout(&mut self) {
         self.mock_app.layout()
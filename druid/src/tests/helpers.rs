@@ -23,6 +23,7 @@
 
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::fmt::Write;
 use std::rc::Rc;
 
 use crate::*;
@@ -269,11 +270,95 @@ impl Recording {
             .into_iter()
     }
 
+    /// Drains the recording and asserts that it matches `expected` exactly.
+    ///
+    /// Each entry of `expected` is compared with the [`Record::kind`] of the
+    /// record at the same position. On mismatch this panics with a side-by-side
+    /// listing of the expected and recorded kinds.
+    #[track_caller]
+    pub fn assert_kinds(&self, expected: &[&str]) {
+        let actual: Vec<String> = self.drain().map(|record| record.kind()).collect();
+        let matches = actual.len() == expected.len()
+            && actual.iter().zip(expected).all(|(a, e)| a.as_str() == *e);
+        if !matches {
+            panic!("{}", expectation_diff(expected, &actual));
+        }
+    }
+
+    /// Drains the recording and asserts that the kinds in `expected` occur in it,
+    /// in order.
+    ///
+    /// Unlike [`assert_kinds`], records that are not mentioned in `expected` are
+    /// skipped, which keeps tests robust against unrelated internal events.
+    ///
+    /// [`assert_kinds`]: Recording::assert_kinds
+    #[track_caller]
+    pub fn assert_contains_kinds(&self, expected: &[&str]) {
+        let actual: Vec<String> = self.drain().map(|record| record.kind()).collect();
+        let mut next = 0;
+        for kind in &actual {
+            if next < expected.len() && expected[next] == kind.as_str() {
+                next += 1;
+            }
+        }
+        if next < expected.len() {
+            panic!(
+                "{}missing from position {next}: {}",
+                expectation_diff(expected, &actual),
+                expected[next]
+            );
+        }
+    }
+
     fn push(&self, event: Record) {
         self.0.borrow_mut().push_back(event)
     }
 }
 
+impl Record {
+    /// A short description of this record, used when asserting on a [`Recording`].
+    ///
+    /// Events and lifecycle events are described by their variant name, for
+    /// instance `E(MouseDown)` or `L(WidgetAdded)`; the other records are
+    /// `Layout`, `Update`, `Paint` and `None`.
+    pub fn kind(&self) -> String {
+        match self {
+            Record::E(event) => format!("E({})", variant_name(&format!("{event:?}"))),
+            Record::L(event) => format!("L({})", variant_name(&format!("{event:?}"))),
+            Record::Layout(_) => "Layout".to_string(),
+            Record::Update(_) => "Update".to_string(),
+            Record::Paint => "Paint".to_string(),
+            Record::None => "None".to_string(),
+        }
+    }
+}
+
+/// The name of an enum variant, given its `Debug` representation.
+fn variant_name(debug: &str) -> &str {
+    debug
+        .split(|c: char| c == '(' || c == '{' || c == ' ')
+        .next()
+        .unwrap_or(debug)
+}
+
+fn expectation_diff(expected: &[&str], actual: &[String]) -> String {
+    let width = expected
+        .iter()
+        .map(|kind| kind.len())
+        .max()
+        .unwrap_or(0)
+        .max("expected".len());
+    let mut out = String::from("recording did not match expectation:\n");
+    writeln!(out, "    {:width$} | actual", "expected").unwrap();
+    for i in 0..expected.len().max(actual.len()) {
+        let e = expected.get(i).copied().unwrap_or("");
+        let a = actual.get(i).map(String::as_str).unwrap_or("");
+        let marker = if e == a { "  " } else { "> " };
+        writeln!(out, "  {marker}{e:width$} | {a}").unwrap();
+    }
+    out
+}
+
 impl<T: Data, W: Widget<T>> Widget<T> for Recorder<W> {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         self.recording.push(Record::E(event.clone()));
@@ -762,6 +762,139 @@ fn simple_lifecyle() {
     })
 }
 
+#[test]
+fn recording_expectations() {
+    const SET_DATA: Selector = Selector::new("druid-tests.set-data");
+    let record = Recording::default();
+    let widget = ModularWidget::new(())
+        .event_fn(|_, _ctx, event, data: &mut bool, _env| {
+            if matches!(event, Event::Command(cmd) if cmd.is(SET_DATA)) {
+                *data = true;
+            }
+        })
+        .record(&record);
+
+    Harness::create_simple(false, widget, |harness| {
+        harness.send_initial_events();
+        record.assert_kinds(&[
+            "L(WidgetAdded)",
+            "L(BuildFocusChain)",
+            "E(WindowConnected)",
+            "E(WindowSize)",
+        ]);
+
+        harness.assert_data_after(true, |harness| harness.submit_command(SET_DATA));
+        record.assert_contains_kinds(&["E(Command)", "Update"]);
+        assert!(record.is_empty());
+    })
+}
+
+#[test]
+fn simulated_timers_and_frames() {
+    use std::time::Duration;
+
+    struct Clocked {
+        timer: TimerToken,
+        timer_fired: bool,
+        frame_intervals: Vec<u64>,
+    }
+
+    let state = Rc::new(std::cell::RefCell::new(Clocked {
+        timer: TimerToken::INVALID,
+        timer_fired: false,
+        frame_intervals: Vec::new(),
+    }));
+    let state_clone = state.clone();
+    let widget = ModularWidget::new(()).event_fn(move |_, ctx, event, _data: &mut (), _env| {
+        let mut state = state_clone.borrow_mut();
+        match event {
+            Event::WindowConnected => {
+                state.timer = ctx.request_timer(Duration::from_millis(100));
+                ctx.request_anim_frame();
+            }
+            Event::Timer(token) if *token == state.timer => state.timer_fired = true,
+            Event::AnimFrame(interval) => {
+                state.frame_intervals.push(*interval);
+                if state.frame_intervals.len() < 3 {
+                    ctx.request_anim_frame();
+                }
+            }
+            _ => (),
+        }
+    });
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        assert!(state.borrow().frame_intervals.is_empty());
+
+        harness.advance(Duration::from_millis(60));
+        assert!(!state.borrow().timer_fired);
+        let interval = harness::ANIM_FRAME_INTERVAL.as_nanos() as u64;
+        assert_eq!(state.borrow().frame_intervals, vec![0, interval, interval]);
+
+        harness.advance(Duration::from_millis(60));
+        assert!(state.borrow().timer_fired);
+        assert_eq!(state.borrow().frame_intervals.len(), 3);
+        assert_eq!(harness.elapsed(), Duration::from_millis(120));
+    })
+}
+
+#[test]
+fn window_activation_changes_env() {
+    let seen = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let seen_clone = seen.clone();
+    let widget = ModularWidget::new(()).update_fn(move |_, ctx, _, _: &(), env| {
+        if ctx.env_key_changed(&theme::WINDOW_IS_ACTIVE) {
+            seen_clone
+                .borrow_mut()
+                .push(env.get(theme::WINDOW_IS_ACTIVE));
+        }
+    });
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        assert!(seen.borrow().is_empty());
+
+        // This mirrors what the window handler does when the platform reports focus changes.
+        harness.window_mut().set_active(false);
+        harness.event(Event::Internal(InternalEvent::MouseLeave));
+        harness.window_mut().set_active(true);
+        harness.event(Event::Internal(InternalEvent::MouseLeave));
+        assert_eq!(*seen.borrow(), vec![false, true]);
+    })
+}
+
+#[test]
+fn slow_paint_puts_next_frame_under_load() {
+    use std::time::Duration;
+
+    let under_load = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let under_load_clone = under_load.clone();
+    let widget = ModularWidget::new(()).paint_fn(move |_, ctx, _: &(), _| {
+        let mut under_load = under_load_clone.borrow_mut();
+        under_load.push(ctx.is_under_load());
+        if under_load.len() == 1 {
+            // Painting the first frame takes longer than the frame budget.
+            let clock = ctx.state.simulated_timers.as_mut().unwrap();
+            clock.now += Duration::from_millis(20);
+        }
+        // A child with a budget of its own runs out of it before the frame does.
+        ctx.state.simulated_timers.as_mut().unwrap().now += Duration::from_millis(1);
+        ctx.with_budget(Duration::from_millis(2), |ctx| {
+            ctx.state.simulated_timers.as_mut().unwrap().now += Duration::from_millis(3);
+            assert!(ctx.is_under_load());
+        });
+    });
+
+    Harness::create_simple((), widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        harness.paint();
+        harness.paint();
+        assert_eq!(*under_load.borrow(), vec![false, true, false]);
+    })
+}
+
 #[test]
 /// Test that lifecycle events are sent correctly to a child added during event
 /// handling
@@ -118,6 +118,43 @@ pub struct ParseFormatter<T> {
     fmt_fn: Box<dyn Fn(&T) -> String>,
 }
 
+/// A [`Formatter`] for numbers written with the conventions of a locale.
+///
+/// Different languages use different characters to separate the integer and
+/// fractional parts of a number, and to group the digits of large numbers; a
+/// German user writes `1.234,56` for what an English user writes as
+/// `1,234.56`. This formatter displays numbers with grouped digits, and accepts
+/// input with or without grouping separators.
+///
+/// # Examples
+///
+/// ```
+/// use druid::text::{Formatter, NumberFormatter};
+///
+/// let german = NumberFormatter::for_locale("de-DE").with_precision(2);
+/// assert_eq!(german.format(&1234.5), "1.234,50");
+/// assert_eq!(german.value("1.234,56").unwrap(), 1234.56);
+/// assert_eq!(german.value("1234,56").unwrap(), 1234.56);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NumberFormatter {
+    decimal: char,
+    grouping: Option<char>,
+    precision: Option<usize>,
+}
+
+#[derive(Debug)]
+struct InvalidNumber;
+
+/// Languages that write numbers like `1.234,5`.
+const COMMA_AND_DOT: &[&str] = &[
+    "da", "de", "el", "es", "hr", "id", "it", "nl", "pt", "ro", "sl", "sr", "tr",
+];
+/// Languages that write numbers like `1 234,5`.
+const COMMA_AND_SPACE: &[&str] = &[
+    "bg", "cs", "et", "fi", "fr", "hu", "lt", "lv", "nb", "no", "pl", "ru", "sk", "sv", "uk",
+];
+
 impl Validation {
     /// Create a `Validation` indicating success.
     pub fn success() -> Self {
@@ -208,6 +245,152 @@ where
     }
 }
 
+impl NumberFormatter {
+    /// Create a formatter that uses `.` as the decimal separator and `,` to
+    /// group digits.
+    pub fn new() -> Self {
+        NumberFormatter::with_separators('.', Some(','))
+    }
+
+    /// Create a formatter with the given decimal and grouping separators.
+    ///
+    /// If the grouping separator is whitespace, any whitespace is accepted
+    /// as a grouping separator in the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `grouping` is the same as `decimal`.
+    pub fn with_separators(decimal: char, grouping: Option<char>) -> Self {
+        assert!(grouping != Some(decimal), "separators must differ");
+        NumberFormatter {
+            decimal,
+            grouping,
+            precision: None,
+        }
+    }
+
+    /// Create a formatter using the separators of the locale with the given
+    /// language tag, such as `"de-DE"` or `"fr"`.
+    ///
+    /// Unknown languages use the same separators as [`NumberFormatter::new`].
+    pub fn for_locale(tag: &str) -> Self {
+        let mut subtags = tag.split(['-', '_']);
+        let language = subtags.next().unwrap_or_default().to_ascii_lowercase();
+        let region = subtags.last().unwrap_or_default().to_ascii_uppercase();
+        let (decimal, grouping) = if region == "CH" && (language == "de" || language == "it") {
+            ('.', '\'')
+        } else if COMMA_AND_DOT.contains(&language.as_str()) {
+            (',', '.')
+        } else if COMMA_AND_SPACE.contains(&language.as_str()) {
+            (',', '\u{a0}')
+        } else {
+            ('.', ',')
+        };
+        NumberFormatter::with_separators(decimal, Some(grouping))
+    }
+
+    /// Builder-style method to always show `digits` fractional digits.
+    ///
+    /// By default, numbers are shown with as many digits as they need.
+    pub fn with_precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    fn format_with_grouping(&self, value: f64, grouping: Option<char>) -> String {
+        let plain = match self.precision {
+            Some(digits) => format!("{value:.digits$}"),
+            None => value.to_string(),
+        };
+        let (sign, unsigned) = match plain.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", plain.as_str()),
+        };
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut out = String::from(sign);
+        for (i, c) in integer.chars().enumerate() {
+            if let Some(grouping) = grouping {
+                if i > 0 && (integer.len() - i) % 3 == 0 {
+                    out.push(grouping);
+                }
+            }
+            out.push(c);
+        }
+        if let Some(fraction) = fraction {
+            out.push(self.decimal);
+            out.push_str(fraction);
+        }
+        out
+    }
+
+    fn is_grouping(&self, c: char) -> bool {
+        match self.grouping {
+            Some(grouping) if grouping.is_whitespace() => c.is_whitespace(),
+            Some(grouping) => c == grouping,
+            None => false,
+        }
+    }
+
+    /// Convert (possibly partial) input to the notation `f64::from_str` expects.
+    fn normalize(&self, input: &str) -> Result<String, InvalidNumber> {
+        let mut out = String::with_capacity(input.len());
+        let mut seen_decimal = false;
+        for (i, c) in input.trim().chars().enumerate() {
+            match c {
+                '-' | '+' if i == 0 => out.push(c),
+                '0'..='9' => out.push(c),
+                c if c == self.decimal && !seen_decimal => {
+                    seen_decimal = true;
+                    out.push('.');
+                }
+                c if !seen_decimal && self.is_grouping(c) => (),
+                _ => return Err(InvalidNumber),
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl Formatter<f64> for NumberFormatter {
+    fn format(&self, value: &f64) -> String {
+        self.format_with_grouping(*value, self.grouping)
+    }
+
+    fn format_for_editing(&self, value: &f64) -> String {
+        self.format_with_grouping(*value, None)
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        match self.normalize(input) {
+            Ok(_) => Validation::success(),
+            Err(e) => Validation::failure(e),
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<f64, ValidationError> {
+        let normalized = self.normalize(input).map_err(ValidationError::new)?;
+        normalized.parse().map_err(ValidationError::new)
+    }
+}
+
+impl Default for NumberFormatter {
+    fn default() -> Self {
+        NumberFormatter::new()
+    }
+}
+
+impl std::fmt::Display for InvalidNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "not a valid number")
+    }
+}
+
+impl std::error::Error for InvalidNumber {}
+
 impl std::fmt::Display for ValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", &self.inner)
@@ -225,3 +408,54 @@ impl<T: std::fmt::Display> Default for ParseFormatter<T> {
         ParseFormatter::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn numbers_follow_locale_conventions() {
+        let english = NumberFormatter::for_locale("en-US").with_precision(2);
+        assert_eq!(english.format(&-1234567.5), "-1,234,567.50");
+        assert_eq!(english.format_for_editing(&1234.5), "1234.50");
+        assert_eq!(english.value("1,234.5").unwrap(), 1234.5);
+
+        let german = NumberFormatter::for_locale("de_AT");
+        assert_eq!(german.format(&1234.5), "1.234,5");
+        assert_eq!(german.value("1.234,56").unwrap(), 1234.56);
+
+        let french = NumberFormatter::for_locale("fr");
+        assert_eq!(french.value("1 234,5").unwrap(), 1234.5);
+        assert_eq!(
+            NumberFormatter::for_locale("de-CH").format(&1000.0),
+            "1'000"
+        );
+    }
+
+    #[test]
+    fn partial_numbers() {
+        let german = NumberFormatter::for_locale("de");
+        let sel = Selection::caret(0);
+        assert!(!german.validate_partial_input("-", &sel).is_err());
+        assert!(!german.validate_partial_input("12,", &sel).is_err());
+        assert!(german.validate_partial_input("1,2,3", &sel).is_err());
+        assert!(german.validate_partial_input("1,2.3", &sel).is_err());
+        assert!(german.validate_partial_input("12a", &sel).is_err());
+        assert!(german.value("-").is_err());
+        assert!(german.value("").is_err());
+    }
+
+    #[test]
+    fn validation_builders() {
+        let ok = Validation::success().change_text("12".into());
+        assert!(!ok.is_err());
+        assert!(ok.error().is_none());
+        assert_eq!(ok.text_change.as_deref(), Some("12"));
+
+        let failed = Validation::failure(InvalidNumber).change_selection(Selection::caret(1));
+        assert!(failed.is_err());
+        assert!(failed.error().is_some());
+        assert_eq!(failed.selection_change, Some(Selection::caret(1)));
+    }
+}
@@ -24,6 +24,14 @@ use crate::{Env, FontDescriptor, FontFamily, FontStyle, FontWeight, Insets, Key}
 
 pub const WINDOW_BACKGROUND_COLOR: Key<Color> =
     Key::new("org.linebender.druid.theme.window_background_color");
+/// Whether the widget's window is the active window, the one receiving keyboard input.
+///
+/// This is maintained by druid for each window. Widgets can use it to dim
+/// their appearance, or to stop animating, while the window is inactive;
+/// check [`UpdateCtx::env_key_changed`] to find out when it changes.
+///
+/// [`UpdateCtx::env_key_changed`]: crate::UpdateCtx::env_key_changed
+pub const WINDOW_IS_ACTIVE: Key<bool> = Key::new("org.linebender.druid.theme.window_is_active");
 
 #[doc(hidden)]
 #[deprecated(since = "0.8.0", note = "renamed to TEXT_COLOR")]
@@ -122,9 +130,53 @@ pub const SCROLLBAR_EDGE_WIDTH: Key<f64> =
 /// scrollbar's primary axis.
 pub const SCROLLBAR_MIN_SIZE: Key<f64> = Key::new("org.linebender.theme.scrollbar_min_size");
 
+/// The top color of the gradient filling the track of a [`Switch`] that is on.
+///
+/// Switches used [`PRIMARY_LIGHT`] for this before.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_TRACK_ON_LIGHT: Key<Color> =
+    Key::new("org.linebender.druid.theme.switch_track_on_light");
+/// The bottom color of the gradient filling the track of a [`Switch`] that is on.
+///
+/// Switches used [`PRIMARY_DARK`] for this before.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_TRACK_ON_DARK: Key<Color> =
+    Key::new("org.linebender.druid.theme.switch_track_on_dark");
+/// The top color of the gradient filling the track of a [`Switch`] that is off.
+///
+/// Switches used [`BACKGROUND_LIGHT`] for this before.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_TRACK_OFF_LIGHT: Key<Color> =
+    Key::new("org.linebender.druid.theme.switch_track_off_light");
+/// The bottom color of the gradient filling the track of a [`Switch`] that is off.
+///
+/// Switches used [`BACKGROUND_DARK`] for this before.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_TRACK_OFF_DARK: Key<Color> =
+    Key::new("org.linebender.druid.theme.switch_track_off_dark");
+/// The top color of the gradient filling the knob of a [`Switch`], which moves
+/// to the bottom while the knob is pressed.
+///
+/// Switches used [`FOREGROUND_LIGHT`] for this before.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_KNOB_LIGHT: Key<Color> = Key::new("org.linebender.druid.theme.switch_knob_light");
+/// The bottom color of the gradient filling the knob of a [`Switch`], which
+/// moves to the top while the knob is pressed.
+///
+/// Switches used [`FOREGROUND_DARK`] for this before.
+///
+/// [`Switch`]: crate::widget::Switch
+pub const SWITCH_KNOB_DARK: Key<Color> = Key::new("org.linebender.druid.theme.switch_knob_dark");
+
 /// An initial theme.
 pub(crate) fn add_to_env(env: Env) -> Env {
     env.adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
+        .adding(WINDOW_IS_ACTIVE, true)
         .adding(TEXT_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
         .adding(DISABLED_TEXT_COLOR, Color::rgb8(0xa0, 0xa0, 0x9a))
         .adding(PLACEHOLDER_COLOR, Color::rgb8(0x80, 0x80, 0x80))
@@ -169,6 +221,12 @@ pub(crate) fn add_to_env(env: Env) -> Env {
         .adding(SCROLLBAR_MIN_SIZE, 45.)
         .adding(SCROLLBAR_RADIUS, 5.)
         .adding(SCROLLBAR_EDGE_WIDTH, 1.)
+        .adding(SWITCH_TRACK_ON_LIGHT, Color::rgb8(0x5c, 0xc4, 0xff))
+        .adding(SWITCH_TRACK_ON_DARK, Color::rgb8(0x00, 0x8d, 0xdd))
+        .adding(SWITCH_TRACK_OFF_LIGHT, Color::rgb8(0x3a, 0x3a, 0x3a))
+        .adding(SWITCH_TRACK_OFF_DARK, Color::rgb8(0x31, 0x31, 0x31))
+        .adding(SWITCH_KNOB_LIGHT, Color::rgb8(0xf9, 0xf9, 0xf9))
+        .adding(SWITCH_KNOB_DARK, Color::rgb8(0xbf, 0xbf, 0xbf))
         .adding(WIDGET_PADDING_VERTICAL, 10.0)
         .adding(WIDGET_PADDING_HORIZONTAL, 8.0)
         .adding(WIDGET_CONTROL_COMPONENT_PADDING, 4.0)
@@ -0,0 +1,534 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A bar showing a path of clickable segments.
+
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::popup;
+use crate::widget::prelude::*;
+use crate::widget::ListIter;
+use crate::{theme, MouseButton, Point, Rect, Selector, TextLayout, WidgetPod};
+
+/// The horizontal padding around the text of each segment.
+const SEGMENT_PADDING: f64 = 6.0;
+/// The text shown between segments.
+const SEPARATOR: &str = "\u{203a}";
+/// The text of the segment that stands for the collapsed ones.
+const OVERFLOW: &str = "\u{2026}";
+
+/// Sent from the overflow menu to the bar when a segment is picked.
+const SELECT_SEGMENT: Selector<usize> = Selector::new("druid-builtin.breadcrumb.select-segment");
+
+type SegmentCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, usize, &Env)>;
+
+/// A bar showing a path, such as the folders leading to a file, as a row of
+/// clickable segments.
+///
+/// The data is a list of the segments' labels. Clicking a segment submits a
+/// [`Breadcrumb::SEGMENT_CLICKED`] notification with the index of the
+/// segment, and calls the closure set with [`on_segment_click`], if any.
+///
+/// When the segments don't fit, the ones after the first are collapsed into a
+/// single `…` segment, starting with the outermost; clicking it opens a menu
+/// with the collapsed segments.
+///
+/// [`on_segment_click`]: Breadcrumb::on_segment_click
+pub struct Breadcrumb<T> {
+    // The overflow menu needs a pod of its own to sync with; see the `popup` module.
+    inner: WidgetPod<T, BreadcrumbBar<T>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    Segment(usize),
+    Overflow,
+}
+
+struct BreadcrumbBar<T> {
+    on_click: Option<SegmentCallback<T>>,
+    labels: Vec<TextLayout<String>>,
+    separator: TextLayout<String>,
+    overflow: TextLayout<String>,
+    /// The segments that are collapsed into the overflow segment.
+    hidden: Range<usize>,
+    /// The items that are shown, and where.
+    items: Vec<(Item, Rect)>,
+    hot: Option<Item>,
+    pressed: Option<Item>,
+}
+
+/// The contents of the menu with the collapsed segments.
+struct OverflowMenu {
+    hidden: Range<usize>,
+    /// The bar to send the picked segment to.
+    target: WidgetId,
+    labels: Vec<TextLayout<String>>,
+    hot: Option<usize>,
+}
+
+impl Breadcrumb<()> {
+    /// The notification submitted when a segment is clicked, with the index
+    /// of the segment.
+    pub const SEGMENT_CLICKED: Selector<usize> =
+        Selector::new("druid-builtin.breadcrumb.segment-clicked");
+}
+
+impl<T: ListIter<String>> Breadcrumb<T> {
+    /// Create a new `Breadcrumb`.
+    pub fn new() -> Self {
+        Breadcrumb {
+            inner: WidgetPod::new(BreadcrumbBar {
+                on_click: None,
+                labels: Vec::new(),
+                separator: TextLayout::from_text(SEPARATOR.to_string()),
+                overflow: TextLayout::from_text(OVERFLOW.to_string()),
+                hidden: 0..0,
+                items: Vec::new(),
+                hot: None,
+                pressed: None,
+            }),
+        }
+    }
+
+    /// Builder-style method to set a closure that is called with the index
+    /// of a segment when it is clicked.
+    pub fn on_segment_click(
+        mut self,
+        f: impl Fn(&mut EventCtx, &mut T, usize, &Env) + 'static,
+    ) -> Self {
+        self.inner.widget_mut().on_click = Some(Box::new(f));
+        self
+    }
+}
+
+impl<T: ListIter<String>> Default for Breadcrumb<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The segments to collapse so that the path fits in `available` width.
+///
+/// The first segment and as many of the last segments as fit are kept; the
+/// last segment is kept even if it doesn't fit.
+fn collapsed_segments(
+    widths: &[f64],
+    separator: f64,
+    overflow: f64,
+    available: f64,
+) -> Range<usize> {
+    let total = widths.iter().sum::<f64>() + separator * widths.len().saturating_sub(1) as f64;
+    if total <= available || widths.len() < 3 {
+        return 1..1;
+    }
+    let mut used = widths[0] + separator + overflow + separator + widths[widths.len() - 1];
+    let mut first_shown = widths.len() - 1;
+    while first_shown > 2 && used + widths[first_shown - 1] + separator <= available {
+        first_shown -= 1;
+        used += widths[first_shown] + separator;
+    }
+    1..first_shown
+}
+
+impl<T: ListIter<String>> BreadcrumbBar<T> {
+    fn segment_width(label: &TextLayout<String>) -> f64 {
+        label.size().width + SEGMENT_PADDING * 2.0
+    }
+
+    fn item_at(&self, pos: Point) -> Option<Item> {
+        self.items
+            .iter()
+            .find(|(_, rect)| rect.contains(pos))
+            .map(|(item, _)| *item)
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, data: &mut T, idx: usize, env: &Env) {
+        trace!("Segment {} clicked", idx);
+        if let Some(on_click) = &self.on_click {
+            on_click(ctx, data, idx, env);
+        }
+        ctx.submit_notification(Breadcrumb::SEGMENT_CLICKED.with(idx));
+    }
+
+    fn open_overflow_menu(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        let overflow_rect = self
+            .items
+            .iter()
+            .find(|(item, _)| *item == Item::Overflow)
+            .map_or(Rect::ZERO, |(_, rect)| *rect);
+        let width = self.labels[self.hidden.clone()]
+            .iter()
+            .map(Self::segment_width)
+            .fold(0.0, f64::max);
+        let size = Size::new(width, popup::ROW_HEIGHT * self.hidden.len() as f64);
+        let origin = Point::new(overflow_rect.x0, ctx.size().height);
+        let config = popup::config(ctx, origin, size);
+        let menu = OverflowMenu {
+            hidden: self.hidden.clone(),
+            target: ctx.widget_id(),
+            labels: Vec::new(),
+            hot: None,
+        };
+        ctx.new_sub_window(config, menu, data.clone(), env.clone());
+    }
+}
+
+impl<T: ListIter<String>> Widget<T> for Breadcrumb<T> {
+    #[instrument(
+        name = "Breadcrumb",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Breadcrumb",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Breadcrumb",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Breadcrumb", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Breadcrumb", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut path = Vec::new();
+        data.for_each(|segment, _| path.push(segment.clone()));
+        DebugState {
+            display_name: "Breadcrumb".to_string(),
+            main_value: path.join(" / "),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: ListIter<String>> Widget<T> for BreadcrumbBar<T> {
+    #[instrument(
+        name = "BreadcrumbBar",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseMove(mouse) => {
+                let hot = self.item_at(mouse.pos);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if !ctx.is_disabled() {
+                    self.pressed = self.item_at(mouse.pos);
+                    ctx.set_active(self.pressed.is_some());
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && ctx.is_active() => {
+                ctx.set_active(false);
+                let pressed = self.pressed.take();
+                if pressed.is_some() && pressed == self.item_at(mouse.pos) {
+                    match pressed {
+                        Some(Item::Segment(idx)) => self.select(ctx, data, idx, env),
+                        Some(Item::Overflow) => self.open_overflow_menu(ctx, data, env),
+                        None => (),
+                    }
+                }
+                ctx.request_paint();
+            }
+            Event::Command(cmd) if cmd.is(SELECT_SEGMENT) => {
+                let idx = *cmd.get_unchecked(SELECT_SEGMENT);
+                self.select(ctx, data, idx, env);
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "BreadcrumbBar",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                data.for_each(|segment, _| {
+                    self.labels.push(TextLayout::from_text(segment.clone()));
+                });
+            }
+            LifeCycle::HotChanged(false) => {
+                self.hot = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "BreadcrumbBar",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
+            self.labels.truncate(data.data_len());
+            data.for_each(|segment, idx| match self.labels.get_mut(idx) {
+                Some(label) => label.set_text(segment.clone()),
+                None => self.labels.push(TextLayout::from_text(segment.clone())),
+            });
+            ctx.request_layout();
+        }
+        let mut needs_layout = self.separator.needs_rebuild_after_update(ctx);
+        needs_layout |= self.overflow.needs_rebuild_after_update(ctx);
+        for label in self.labels.iter_mut() {
+            needs_layout |= label.needs_rebuild_after_update(ctx);
+        }
+        if needs_layout {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(
+        name = "BreadcrumbBar",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Breadcrumb");
+        for label in self.labels.iter_mut() {
+            label.rebuild_if_needed(ctx.text(), env);
+        }
+        self.separator.rebuild_if_needed(ctx.text(), env);
+        self.overflow.rebuild_if_needed(ctx.text(), env);
+
+        let widths: Vec<f64> = self.labels.iter().map(Self::segment_width).collect();
+        let separator_width = self.separator.size().width;
+        let overflow_width = Self::segment_width(&self.overflow);
+        self.hidden = collapsed_segments(&widths, separator_width, overflow_width, bc.max().width);
+
+        let text_height = self.separator.size().height;
+        let height = env.get(theme::BORDERED_WIDGET_HEIGHT).max(text_height);
+        self.items.clear();
+        let mut x = 0.0;
+        for (idx, width) in widths.iter().enumerate() {
+            let (item, width) = if idx == self.hidden.start && !self.hidden.is_empty() {
+                (Item::Overflow, overflow_width)
+            } else if self.hidden.contains(&idx) {
+                continue;
+            } else {
+                (Item::Segment(idx), *width)
+            };
+            if !self.items.is_empty() {
+                x += separator_width;
+            }
+            self.items
+                .push((item, Rect::new(x, 0.0, x + width, height)));
+            x += width;
+        }
+
+        let size = bc.constrain(Size::new(x, height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "BreadcrumbBar", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let height = ctx.size().height;
+        let clip = ctx.size().to_rect();
+        ctx.clip(clip);
+        let last = self.labels.len().saturating_sub(1);
+        for (n, (item, rect)) in self.items.iter().enumerate() {
+            if n > 0 {
+                let y = (height - self.separator.size().height) / 2.0;
+                let x = rect.x0 - self.separator.size().width;
+                self.separator.draw(ctx, (x, y));
+            }
+            let label = match item {
+                Item::Segment(idx) => &mut self.labels[*idx],
+                Item::Overflow => &mut self.overflow,
+            };
+            if self.hot == Some(*item) && !ctx.is_disabled() {
+                let background = rect.to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+                ctx.fill(background, &env.get(theme::BACKGROUND_LIGHT));
+            }
+            let color = if ctx.is_disabled() {
+                theme::DISABLED_TEXT_COLOR
+            } else if *item == Item::Segment(last) {
+                theme::TEXT_COLOR
+            } else {
+                theme::PRIMARY_LIGHT
+            };
+            label.set_text_color(color);
+            label.rebuild_if_needed(ctx.text(), env);
+            let y = (height - label.size().height) / 2.0;
+            label.draw(ctx, (rect.x0 + SEGMENT_PADDING, y));
+        }
+    }
+}
+
+impl<T: ListIter<String>> Widget<T> for OverflowMenu {
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        popup::event(ctx, event);
+        match event {
+            Event::MouseMove(mouse) => {
+                let hot = popup::row_at(mouse.pos.y, self.hidden.len());
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if let Some(row) = popup::row_at(mouse.pos.y, self.hidden.len()) {
+                    let idx = self.hidden.start + row;
+                    ctx.submit_command(SELECT_SEGMENT.with(idx).to(self.target));
+                    ctx.window().close();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let hidden = self.hidden.clone();
+            data.for_each(|segment, idx| {
+                if hidden.contains(&idx) {
+                    self.labels.push(TextLayout::from_text(segment.clone()));
+                }
+            });
+        }
+        popup::lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, _old_data, _data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, env: &Env) {
+        popup::update(ctx, env);
+    }
+
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        for label in self.labels.iter_mut() {
+            label.rebuild_if_needed(ctx.text(), env);
+        }
+        bc.max()
+    }
+
+    #[instrument(name = "OverflowMenu", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        popup::paint_background(ctx, env);
+        for (row, label) in self.labels.iter().enumerate() {
+            let y = popup::ROW_HEIGHT * row as f64;
+            if self.hot == Some(row) {
+                popup::paint_highlight(ctx, y, env);
+            }
+            let text_y = y + (popup::ROW_HEIGHT - label.size().height) / 2.0;
+            label.draw(ctx, (SEGMENT_PADDING, text_y));
+        }
+        popup::paint_border(ctx, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::{MouseButtons, MouseEvent};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[test]
+    fn segments_collapse_from_the_outside() {
+        let widths = [50.0, 40.0, 40.0, 40.0, 60.0];
+        assert_eq!(collapsed_segments(&widths, 10.0, 20.0, 1000.0), 1..1);
+        // The first and the last segment, and the overflow segment, take 150.
+        assert_eq!(collapsed_segments(&widths, 10.0, 20.0, 150.0), 1..4);
+        assert_eq!(collapsed_segments(&widths, 10.0, 20.0, 200.0), 1..3);
+        assert_eq!(collapsed_segments(&widths, 10.0, 20.0, 10.0), 1..4);
+    }
+
+    #[test]
+    fn clicking_a_segment_reports_its_index() {
+        let clicked = Rc::new(Cell::new(None));
+        let clicked_clone = clicked.clone();
+        let path: Arc<Vec<String>> = Arc::new(vec!["home".into(), "docs".into()]);
+        let widget = Breadcrumb::new().on_segment_click(move |_, _, idx, _| {
+            clicked_clone.set(Some(idx));
+        });
+
+        Harness::create_simple(path, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let click = MouseEvent {
+                button: MouseButton::Left,
+                buttons: MouseButtons::new().with(MouseButton::Left),
+                ..move_mouse((SEGMENT_PADDING + 1.0, 5.0))
+            };
+            harness.event(Event::MouseMove(click.clone()));
+            harness.event(Event::MouseDown(click.clone()));
+            harness.event(Event::MouseUp(MouseEvent {
+                buttons: MouseButtons::new(),
+                ..click
+            }));
+            assert_eq!(clicked.get(), Some(0));
+        });
+    }
+}
@@ -0,0 +1,412 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget showing a list of removable tags.
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::im::Vector;
+use crate::kurbo::Line;
+use crate::text::TextComponent;
+use crate::widget::prelude::*;
+use crate::widget::{LabelText, TextBox};
+use crate::{theme, KbKey, Point, Rect, TextLayout, WidgetPod};
+
+/// The space between chips, and between rows of chips.
+const SPACING: f64 = 4.0;
+/// The horizontal padding between the edge of a chip and its text.
+const CHIP_PADDING: f64 = 8.0;
+/// The inset of the remove button from the edges of its chip.
+const REMOVE_INSET: f64 = 4.0;
+/// The smallest width given to the text box for adding chips.
+const INPUT_MIN_WIDTH: f64 = 80.0;
+
+/// A list of strings shown as chips, or tags, that can be removed.
+///
+/// Each chip has a button for removing it. When the widget has keyboard focus,
+/// the left and right arrow keys select a chip, and backspace or delete
+/// removes it.
+///
+/// With [`with_input`], a text box for adding chips follows the last one;
+/// pressing return adds its text as a new chip. Empty text and text that is
+/// already a chip are ignored.
+///
+/// [`with_input`]: Chips::with_input
+pub struct Chips {
+    chips: Vec<Chip>,
+    input: Option<WidgetPod<String, TextBox<String>>>,
+    /// The text of the input.
+    buffer: String,
+    /// The chip selected with the keyboard.
+    selected: Option<usize>,
+    /// The chip whose remove button is under the mouse.
+    hot_remove: Option<usize>,
+    /// The chip whose remove button is being pressed.
+    pressed_remove: Option<usize>,
+}
+
+struct Chip {
+    label: TextLayout<String>,
+    rect: Rect,
+}
+
+impl Chip {
+    /// The area of the button that removes this chip.
+    fn remove_rect(&self) -> Rect {
+        let side = self.rect.height() - REMOVE_INSET * 2.0;
+        Rect::from_origin_size(
+            (
+                self.rect.x1 - REMOVE_INSET - side,
+                self.rect.y0 + REMOVE_INSET,
+            ),
+            (side, side),
+        )
+    }
+}
+
+impl Chips {
+    /// Create a new `Chips` widget.
+    pub fn new() -> Self {
+        Chips {
+            chips: Vec::new(),
+            input: None,
+            buffer: String::new(),
+            selected: None,
+            hot_remove: None,
+            pressed_remove: None,
+        }
+    }
+
+    /// Builder-style method to add a text box for adding chips, showing
+    /// `placeholder` while it is empty.
+    pub fn with_input(mut self, placeholder: impl Into<LabelText<String>>) -> Self {
+        let mut text_box = TextBox::new().with_placeholder(placeholder);
+        text_box.text_mut().borrow_mut().send_notification_on_return = true;
+        self.input = Some(WidgetPod::new(text_box));
+        self
+    }
+
+    fn rebuild_chips(&mut self, data: &Vector<String>) {
+        self.chips = data
+            .iter()
+            .map(|text| Chip {
+                label: TextLayout::from_text(text.clone()),
+                rect: Rect::ZERO,
+            })
+            .collect();
+        self.selected = self
+            .selected
+            .filter(|_| !data.is_empty())
+            .map(|idx| idx.min(data.len() - 1));
+        self.hot_remove = None;
+        self.pressed_remove = None;
+    }
+
+    fn chip_at(&self, pos: Point) -> Option<usize> {
+        self.chips.iter().position(|chip| chip.rect.contains(pos))
+    }
+
+    fn remove_button_at(&self, pos: Point) -> Option<usize> {
+        self.chip_at(pos)
+            .filter(|idx| self.chips[*idx].remove_rect().contains(pos))
+    }
+
+    fn remove(&mut self, ctx: &mut EventCtx, data: &mut Vector<String>, idx: usize) {
+        trace!("Removing chip {}", idx);
+        data.remove(idx);
+        ctx.request_layout();
+    }
+}
+
+impl Default for Chips {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<Vector<String>> for Chips {
+    #[instrument(name = "Chips", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Vector<String>, env: &Env) {
+        if let Some(input) = self.input.as_mut() {
+            input.event(ctx, event, &mut self.buffer, env);
+        }
+
+        match event {
+            Event::Notification(cmd) if cmd.is(TextComponent::RETURN) => {
+                ctx.set_handled();
+                let text = self.buffer.trim();
+                if !text.is_empty() && !data.iter().any(|chip| chip == text) {
+                    data.push_back(text.to_string());
+                    ctx.request_layout();
+                }
+                self.buffer.clear();
+                ctx.request_update();
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() && !ctx.is_disabled() => {
+                if let Some(idx) = self.remove_button_at(mouse.pos) {
+                    self.pressed_remove = Some(idx);
+                    ctx.set_active(true);
+                } else if let Some(idx) = self.chip_at(mouse.pos) {
+                    self.selected = Some(idx);
+                    ctx.request_focus();
+                } else {
+                    return;
+                }
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                if let Some(idx) = self.pressed_remove.take() {
+                    if self.remove_button_at(mouse.pos) == Some(idx) {
+                        self.remove(ctx, data, idx);
+                    }
+                }
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) => {
+                let hot_remove = self.remove_button_at(mouse.pos);
+                if hot_remove != self.hot_remove {
+                    self.hot_remove = hot_remove;
+                    ctx.request_paint();
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                let last = self.chips.len().checked_sub(1);
+                let selected = self.selected;
+                match &key.key {
+                    KbKey::ArrowLeft => self.selected = selected.map(|i| i.saturating_sub(1)),
+                    KbKey::ArrowRight => {
+                        self.selected = selected.zip(last).map(|(i, last)| (i + 1).min(last))
+                    }
+                    KbKey::Home => self.selected = last.map(|_| 0),
+                    KbKey::End => self.selected = last,
+                    KbKey::Backspace | KbKey::Delete => {
+                        if let Some(idx) = selected {
+                            self.remove(ctx, data, idx);
+                            // Backspace moves the selection back, like it does in text.
+                            if key.key == KbKey::Backspace {
+                                self.selected = Some(idx.saturating_sub(1));
+                            }
+                        }
+                    }
+                    KbKey::Tab if key.mods.shift() => ctx.focus_prev(),
+                    KbKey::Tab => ctx.focus_next(),
+                    _ => return,
+                }
+                if self.selected.is_none() {
+                    self.selected = last.map(|_| 0);
+                }
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Chips", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &Vector<String>,
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => self.rebuild_chips(data),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            LifeCycle::HotChanged(false) if self.hot_remove.is_some() => {
+                self.hot_remove = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        if let Some(input) = self.input.as_mut() {
+            input.lifecycle(ctx, event, &self.buffer, env);
+        }
+    }
+
+    #[instrument(name = "Chips", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &Vector<String>,
+        data: &Vector<String>,
+        env: &Env,
+    ) {
+        if !old_data.same(data) {
+            self.rebuild_chips(data);
+            ctx.request_layout();
+        }
+        let mut needs_layout = false;
+        for chip in &mut self.chips {
+            needs_layout |= chip.label.needs_rebuild_after_update(ctx);
+        }
+        if needs_layout {
+            ctx.request_layout();
+        }
+        if let Some(input) = self.input.as_mut() {
+            input.update(ctx, &self.buffer, env);
+        }
+    }
+
+    #[instrument(name = "Chips", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Vector<String>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("Chips");
+        let max_width = bc.max().width;
+        let chip_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let mut pos = Point::ORIGIN;
+        let mut row_height: f64 = 0.0;
+        let mut width: f64 = 0.0;
+        for chip in &mut self.chips {
+            chip.label.rebuild_if_needed(ctx.text(), env);
+            let label_size = chip.label.size();
+            let height = chip_height.max(label_size.height);
+            let size = Size::new(
+                CHIP_PADDING * 1.5 + label_size.width + height - REMOVE_INSET,
+                height,
+            );
+            if pos.x > 0.0 && pos.x + size.width > max_width {
+                pos = Point::new(0.0, pos.y + row_height + SPACING);
+                row_height = 0.0;
+            }
+            chip.rect = Rect::from_origin_size(pos, size);
+            pos.x += size.width + SPACING;
+            row_height = row_height.max(size.height);
+            width = width.max(chip.rect.x1);
+        }
+
+        if let Some(input) = self.input.as_mut() {
+            if pos.x > 0.0 && pos.x + INPUT_MIN_WIDTH > max_width {
+                pos = Point::new(0.0, pos.y + row_height + SPACING);
+                row_height = 0.0;
+            }
+            let input_width = if max_width.is_finite() {
+                (max_width - pos.x).max(INPUT_MIN_WIDTH)
+            } else {
+                INPUT_MIN_WIDTH
+            };
+            let input_bc = BoxConstraints::new(
+                Size::new(input_width, 0.0),
+                Size::new(input_width, f64::INFINITY),
+            );
+            let input_size = input.layout(ctx, &input_bc, &self.buffer, env);
+            input.set_origin(ctx, pos);
+            row_height = row_height.max(input_size.height);
+            width = width.max(pos.x + input_size.width);
+        }
+
+        let size = bc.constrain(Size::new(width, pos.y + row_height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Chips", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Vector<String>, env: &Env) {
+        let radius = env.get(theme::BUTTON_BORDER_RADIUS);
+        let is_focused = ctx.is_focused();
+        for (idx, chip) in self.chips.iter().enumerate() {
+            let rect = chip.rect.to_rounded_rect(radius);
+            ctx.fill(rect, &env.get(theme::BUTTON_DARK));
+            let border_color = if is_focused && self.selected == Some(idx) {
+                env.get(theme::PRIMARY_LIGHT)
+            } else {
+                env.get(theme::BORDER_DARK)
+            };
+            ctx.stroke(
+                chip.rect.inset(-0.5).to_rounded_rect(radius),
+                &border_color,
+                1.0,
+            );
+
+            let label_y = chip.rect.center().y - chip.label.size().height / 2.0;
+            chip.label.draw(ctx, (chip.rect.x0 + CHIP_PADDING, label_y));
+
+            let remove = chip.remove_rect();
+            if self.hot_remove == Some(idx) || self.pressed_remove == Some(idx) {
+                let circle = remove.to_rounded_rect(remove.height() / 2.0);
+                ctx.fill(circle, &env.get(theme::BUTTON_LIGHT));
+            }
+            let cross = remove.inset(-remove.height() * 0.3);
+            let color = env.get(theme::TEXT_COLOR);
+            ctx.stroke(Line::new(cross.origin(), (cross.x1, cross.y1)), &color, 1.5);
+            ctx.stroke(
+                Line::new((cross.x0, cross.y1), (cross.x1, cross.y0)),
+                &color,
+                1.5,
+            );
+        }
+
+        if let Some(input) = self.input.as_mut() {
+            input.paint(ctx, &self.buffer, env);
+        }
+    }
+
+    fn debug_state(&self, data: &Vector<String>) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.iter().cloned().collect::<Vec<_>>().join(", "),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::{KeyEvent, Modifiers, MouseButton, MouseButtons, MouseEvent};
+    use test_log::test;
+
+    #[test]
+    fn keyboard_removes_selected_chip() {
+        let data: Vector<String> = ["red", "green", "blue"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let key = |key| Event::KeyDown(KeyEvent::for_test(Modifiers::default(), key));
+
+        Harness::create_simple(data, Chips::new(), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // Clicking the body of the first chip selects it.
+            let click = MouseEvent {
+                button: MouseButton::Left,
+                buttons: MouseButtons::new().with(MouseButton::Left),
+                ..move_mouse((4., 4.))
+            };
+            harness.event(Event::MouseDown(click.clone()));
+            harness.event(Event::MouseUp(click));
+
+            harness.event(key(KbKey::ArrowRight));
+            harness.event(key(KbKey::Backspace));
+            let expected: Vector<String> = vec!["red".to_string(), "blue".to_string()].into();
+            assert_eq!(*harness.data(), expected);
+
+            // The selection moved back to the first chip.
+            harness.event(key(KbKey::Delete));
+            let expected: Vector<String> = vec!["blue".to_string()].into();
+            assert_eq!(*harness.data(), expected);
+        });
+    }
+}
@@ -36,6 +36,8 @@ use tracing::{instrument, trace};
 pub struct Click<T> {
     /// A closure that will be invoked when the child widget is clicked.
     action: Box<dyn Fn(&mut EventCtx, &mut T, &Env)>,
+    /// The mouse button that clicks the child.
+    button: MouseButton,
 }
 
 impl<T: Data> Click<T> {
@@ -43,6 +45,20 @@ impl<T: Data> Click<T> {
     pub fn new(action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
         Click {
             action: Box::new(action),
+            button: MouseButton::Left,
+        }
+    }
+
+    /// Create a new [`Controller`] widget that is clicked with the middle
+    /// mouse button.
+    ///
+    /// See [`MouseEvent::is_middle_click`] for what counts as a middle click.
+    ///
+    /// [`MouseEvent::is_middle_click`]: crate::MouseEvent::is_middle_click
+    pub fn middle(action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        Click {
+            action: Box::new(action),
+            button: MouseButton::Middle,
         }
     }
 }
@@ -56,14 +72,14 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
     fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         match event {
             Event::MouseDown(mouse_event) => {
-                if mouse_event.button == MouseButton::Left && !ctx.is_disabled() {
+                if mouse_event.button == self.button && !ctx.is_disabled() {
                     ctx.set_active(true);
                     ctx.request_paint();
                     trace!("Widget {:?} pressed", ctx.widget_id());
                 }
             }
             Event::MouseUp(mouse_event) => {
-                if ctx.is_active() && mouse_event.button == MouseButton::Left {
+                if ctx.is_active() && mouse_event.button == self.button {
                     ctx.set_active(false);
                     if ctx.is_hot() && !ctx.is_disabled() {
                         (self.action)(ctx, data, env);
@@ -98,3 +114,39 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
         child.lifecycle(ctx, event, data, env);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{SizedBox, WidgetExt};
+    use crate::{MouseButtons, MouseEvent};
+    use test_log::test;
+
+    #[test]
+    fn middle_click_is_separate_from_click() {
+        let widget = SizedBox::empty()
+            .width(100.)
+            .height(100.)
+            .on_click(|_, count: &mut (u32, u32), _| count.0 += 1)
+            .on_middle_click(|_, count: &mut (u32, u32), _| count.1 += 1);
+
+        Harness::create_simple((0, 0), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let middle = MouseEvent {
+                button: MouseButton::Middle,
+                buttons: MouseButtons::new().with(MouseButton::Middle),
+                ..move_mouse((20., 30.))
+            };
+            harness.event(Event::MouseMove(move_mouse((20., 30.))));
+            harness.event(Event::MouseDown(middle.clone()));
+            harness.event(Event::MouseUp(MouseEvent {
+                buttons: MouseButtons::new(),
+                ..middle
+            }));
+            assert_eq!(*harness.data(), (0, 1));
+        });
+    }
+}
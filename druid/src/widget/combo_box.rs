@@ -0,0 +1,516 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A dropdown for picking one of many options.
+
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::widget::popup::{self, ROW_HEIGHT};
+use crate::widget::prelude::*;
+use crate::{
+    commands, theme, ArcStr, KbKey, MouseButton, Point, Rect, TextLayout, WidgetPod, WindowId,
+};
+
+/// The most options the popup shows at once.
+const MAX_VISIBLE_ROWS: usize = 10;
+/// Horizontal padding of the text in the box and in the popup.
+const TEXT_PADDING: f64 = 8.0;
+/// The width reserved for the arrow at the end of the box.
+const ARROW_WIDTH: f64 = 20.0;
+
+type Options<T> = Arc<Vec<(ArcStr, T)>>;
+
+/// A dropdown for picking one of many options, with a popup that can be
+/// filtered by typing.
+///
+/// The data of the combo box is the value of the selected option. Clicking
+/// the box, or pressing the down arrow, enter or space while it has focus,
+/// opens a popup below it with all the options. Typing in the popup shows only
+/// the options whose labels contain the typed text; the arrow keys, page up
+/// and page down move the highlight, enter picks the highlighted option and
+/// escape closes the popup.
+///
+/// The popup only lays out the options it shows, so lists of many thousands of
+/// options stay responsive.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::ComboBox;
+///
+/// let countries = ComboBox::new(vec![("Austria", 43), ("Belgium", 32), ("Chile", 56)]);
+/// ```
+pub struct ComboBox<T> {
+    // The popup needs a pod of its own to sync with; see the `popup` module.
+    inner: WidgetPod<T, ComboBoxButton<T>>,
+}
+
+struct ComboBoxButton<T> {
+    options: Options<T>,
+    label: TextLayout<ArcStr>,
+    popup: Option<WindowId>,
+}
+
+/// The contents of the popup window.
+struct ComboBoxPopup<T> {
+    options: Options<T>,
+    filter: String,
+    filter_layout: TextLayout<String>,
+    /// The indices of the options that match the filter.
+    matches: Vec<usize>,
+    /// The index into `matches` of the highlighted option.
+    highlighted: usize,
+    /// The index into `matches` of the first option that is shown.
+    first_row: usize,
+    visible_rows: usize,
+}
+
+impl<T: Data + PartialEq> ComboBox<T> {
+    /// Create a new `ComboBox` with the given options, as pairs of a label
+    /// and a value.
+    pub fn new(options: impl IntoIterator<Item = (impl Into<ArcStr>, T)>) -> Self {
+        let options = options
+            .into_iter()
+            .map(|(label, value)| (label.into(), value))
+            .collect();
+        ComboBox {
+            inner: WidgetPod::new(ComboBoxButton {
+                options: Arc::new(options),
+                label: TextLayout::new(),
+                popup: None,
+            }),
+        }
+    }
+}
+
+impl<T: Data + PartialEq> ComboBoxButton<T> {
+    fn selected_label(&self, data: &T) -> ArcStr {
+        self.options
+            .iter()
+            .find(|(_, value)| value == data)
+            .map_or_else(|| "".into(), |(label, _)| label.clone())
+    }
+
+    fn open_popup(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        if let Some(old) = self.popup.take() {
+            ctx.submit_command(commands::CLOSE_WINDOW.to(old));
+        }
+        let rows = self.options.len().clamp(1, MAX_VISIBLE_ROWS);
+        // The filter line is one more row at the top.
+        let size = Size::new(ctx.size().width, ROW_HEIGHT * (rows + 1) as f64);
+        let config = popup::config(ctx, Point::new(0.0, ctx.size().height), size);
+        let popup = ComboBoxPopup::new(self.options.clone());
+        trace!("Opening popup with {} options", self.options.len());
+        self.popup = Some(ctx.new_sub_window(config, popup, data.clone(), env.clone()));
+    }
+}
+
+/// The indices of the options whose labels contain `filter`, ignoring case.
+fn matching_options<T>(options: &[(ArcStr, T)], filter: &str) -> Vec<usize> {
+    let filter = filter.to_lowercase();
+    options
+        .iter()
+        .enumerate()
+        .filter(|(_, (label, _))| label.to_lowercase().contains(&filter))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+impl<T: Data + PartialEq> ComboBoxPopup<T> {
+    fn new(options: Options<T>) -> Self {
+        ComboBoxPopup {
+            matches: (0..options.len()).collect(),
+            options,
+            filter: String::new(),
+            filter_layout: TextLayout::new(),
+            highlighted: 0,
+            first_row: 0,
+            visible_rows: MAX_VISIBLE_ROWS,
+        }
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.matches = matching_options(&self.options, &filter);
+        self.filter = filter;
+        self.highlighted = 0;
+        self.first_row = 0;
+    }
+
+    /// Move the highlight by `delta` options, and scroll it into view.
+    fn move_highlight(&mut self, delta: isize) {
+        let last = self.matches.len().saturating_sub(1) as isize;
+        self.highlighted = (self.highlighted as isize + delta).clamp(0, last.max(0)) as usize;
+        if self.highlighted < self.first_row {
+            self.first_row = self.highlighted;
+        } else if self.highlighted >= self.first_row + self.visible_rows {
+            self.first_row = self.highlighted + 1 - self.visible_rows;
+        }
+    }
+
+    /// The index into `matches` of the option at `pos`.
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        let rows = self.matches.len().saturating_sub(self.first_row);
+        popup::row_at(pos.y - ROW_HEIGHT, rows).map(|row| self.first_row + row)
+    }
+
+    fn pick(&mut self, ctx: &mut EventCtx, row: usize, data: &mut T) {
+        if let Some(&idx) = self.matches.get(row) {
+            *data = self.options[idx].1.clone();
+        }
+        ctx.window().close();
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for ComboBox<T> {
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "ComboBox",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "ComboBox", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "ComboBox".to_string(),
+            main_value: self.inner.widget().selected_label(data).to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for ComboBoxButton<T> {
+    #[instrument(
+        name = "ComboBoxButton",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if !ctx.is_disabled() {
+                    ctx.set_active(true);
+                    ctx.request_focus();
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && ctx.is_active() => {
+                ctx.set_active(false);
+                if ctx.is_hot() && !ctx.is_disabled() {
+                    self.open_popup(ctx, data, env);
+                }
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                if matches!(&key.key, KbKey::ArrowDown | KbKey::Enter)
+                    || key.key == KbKey::Character(" ".into())
+                {
+                    self.open_popup(ctx, data, env);
+                    ctx.set_handled();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "ComboBoxButton",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.label.set_text(self.selected_label(data)),
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            LifeCycle::DisabledChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "ComboBoxButton",
+        level = "trace",
+        skip(self, ctx, old_data, data, _env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if !old_data.same(data) {
+            self.label.set_text(self.selected_label(data));
+        }
+        if self.label.needs_rebuild_after_update(ctx) {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(
+        name = "ComboBoxButton",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("ComboBox");
+        self.label.rebuild_if_needed(ctx.text(), env);
+        let text_size = self.label.size();
+        let height = (text_size.height + 4.0).max(env.get(theme::BASIC_WIDGET_HEIGHT));
+        let size = bc.constrain(Size::new(
+            text_size.width + TEXT_PADDING * 2.0 + ARROW_WIDTH,
+            height,
+        ));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "ComboBoxButton", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let size = ctx.size();
+        let rect = size
+            .to_rect()
+            .inset(-0.5)
+            .to_rounded_rect(env.get(theme::BUTTON_BORDER_RADIUS));
+        let border_color = if ctx.is_focused() || ctx.is_hot() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(rect, &border_color, 1.0);
+
+        let text_y = (size.height - self.label.size().height) / 2.0;
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::new(0.0, 0.0, size.width - ARROW_WIDTH, size.height));
+            self.label.draw(ctx, (TEXT_PADDING, text_y));
+        });
+
+        let center = Point::new(size.width - ARROW_WIDTH / 2.0, size.height / 2.0);
+        let mut arrow = BezPath::new();
+        arrow.move_to((center.x - 4.0, center.y - 2.0));
+        arrow.line_to((center.x + 4.0, center.y - 2.0));
+        arrow.line_to((center.x, center.y + 2.0));
+        arrow.close_path();
+        ctx.fill(arrow, &env.get(theme::TEXT_COLOR));
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for ComboBoxPopup<T> {
+    #[instrument(
+        name = "ComboBoxPopup",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, _env: &Env) {
+        popup::event(ctx, event);
+        if ctx.is_handled() {
+            return;
+        }
+        match event {
+            Event::KeyDown(key) => {
+                let page = self.visible_rows as isize;
+                match &key.key {
+                    KbKey::ArrowDown => self.move_highlight(1),
+                    KbKey::ArrowUp => self.move_highlight(-1),
+                    KbKey::PageDown => self.move_highlight(page),
+                    KbKey::PageUp => self.move_highlight(-page),
+                    KbKey::Enter => self.pick(ctx, self.highlighted, data),
+                    KbKey::Backspace => {
+                        let mut filter = self.filter.clone();
+                        filter.pop();
+                        self.set_filter(filter);
+                    }
+                    KbKey::Character(text) if !key.mods.ctrl() && !key.mods.meta() => {
+                        self.set_filter(format!("{}{}", self.filter, text));
+                    }
+                    _ => return,
+                }
+                ctx.set_handled();
+                ctx.request_paint();
+            }
+            Event::Wheel(mouse) => {
+                let max_first = self.matches.len().saturating_sub(self.visible_rows) as isize;
+                let rows = (mouse.wheel_delta.y / ROW_HEIGHT).round() as isize;
+                let first_row = (self.first_row as isize + rows).clamp(0, max_first);
+                self.first_row = first_row as usize;
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) => {
+                if let Some(row) = self.row_at(mouse.pos) {
+                    if row != self.highlighted {
+                        self.highlighted = row;
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if let Some(row) = self.row_at(mouse.pos) {
+                    self.pick(ctx, row, data);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "ComboBoxPopup",
+        level = "trace",
+        skip(self, ctx, event, data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            // Start with the current value highlighted.
+            let selected = self.options.iter().position(|(_, value)| value == data);
+            if let Some(row) = selected {
+                self.move_highlight(row as isize);
+            }
+        }
+        popup::lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "ComboBoxPopup",
+        level = "trace",
+        skip(self, ctx, _old_data, _data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, _data: &T, env: &Env) {
+        popup::update(ctx, env);
+    }
+
+    #[instrument(
+        name = "ComboBoxPopup",
+        level = "trace",
+        skip(self, _ctx, bc, _data, _env)
+    )]
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        let size = bc.max();
+        self.visible_rows = ((size.height / ROW_HEIGHT) as usize)
+            .saturating_sub(1)
+            .max(1);
+        size
+    }
+
+    #[instrument(name = "ComboBoxPopup", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let size = ctx.size();
+        popup::paint_background(ctx, env);
+
+        if self.filter.is_empty() {
+            self.filter_layout.set_text("Type to filter".to_string());
+            self.filter_layout
+                .set_text_color(env.get(theme::PLACEHOLDER_COLOR));
+        } else {
+            self.filter_layout.set_text(self.filter.clone());
+            self.filter_layout
+                .set_text_color(env.get(theme::TEXT_COLOR));
+        }
+        self.filter_layout.rebuild_if_needed(ctx.text(), env);
+        let text_y = (ROW_HEIGHT - self.filter_layout.size().height) / 2.0;
+        self.filter_layout.draw(ctx, (TEXT_PADDING, text_y));
+        ctx.stroke(
+            Line::new((0.0, ROW_HEIGHT - 0.5), (size.width, ROW_HEIGHT - 0.5)),
+            &env.get(theme::BORDER_DARK),
+            1.0,
+        );
+
+        // Only the visible options get a text layout.
+        let visible = self.matches.iter().enumerate().skip(self.first_row);
+        for (row, &idx) in visible.take(self.visible_rows) {
+            let y = ROW_HEIGHT * (row - self.first_row + 1) as f64;
+            if row == self.highlighted {
+                popup::paint_highlight(ctx, y, env);
+            }
+            let mut layout = TextLayout::from_text(self.options[idx].0.clone());
+            layout.rebuild_if_needed(ctx.text(), env);
+            let text_y = y + (ROW_HEIGHT - layout.size().height) / 2.0;
+            layout.draw(ctx, (TEXT_PADDING, text_y));
+        }
+
+        popup::paint_border(ctx, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::{KeyEvent, Modifiers};
+    use test_log::test;
+
+    fn fruit() -> Options<u32> {
+        let names = ["Apple", "Apricot", "Banana", "Grape", "Pineapple"];
+        Arc::new(
+            names
+                .iter()
+                .map(|&n| (n.into(), n.len() as u32 * 10))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn filter_ignores_case() {
+        assert_eq!(matching_options(&fruit(), "ap"), vec![0, 1, 3, 4]);
+        assert_eq!(matching_options(&fruit(), "APP"), vec![0, 4]);
+        assert_eq!(matching_options(&fruit(), ""), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn highlight_stays_visible() {
+        let mut popup = ComboBoxPopup::new(fruit());
+        popup.visible_rows = 2;
+        popup.move_highlight(3);
+        assert_eq!((popup.highlighted, popup.first_row), (3, 2));
+        popup.move_highlight(10);
+        assert_eq!((popup.highlighted, popup.first_row), (4, 3));
+        popup.move_highlight(-4);
+        assert_eq!((popup.highlighted, popup.first_row), (0, 0));
+    }
+
+    #[test]
+    fn typing_filters_and_enter_picks() {
+        let popup = ComboBoxPopup::new(fruit());
+        Harness::create_simple(0, popup, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let key = |key| Event::KeyDown(KeyEvent::for_test(Modifiers::default(), key));
+            harness.event(key(KbKey::Character("p".into())));
+            harness.event(key(KbKey::Character("i".into())));
+            harness.event(key(KbKey::Enter));
+            assert_eq!(*harness.data(), 90);
+        });
+    }
+}
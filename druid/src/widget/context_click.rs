@@ -0,0 +1,144 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] for opening context menus.
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, KbKey, Point, Widget};
+use tracing::{instrument, trace};
+
+/// Whether context menus open when the button is released rather than pressed.
+const OPEN_ON_RELEASE: bool = cfg!(target_os = "windows");
+
+/// A [`Controller`] that calls a closure when the user asks for a context menu
+/// on its child. This is available as an [`on_context_click`] method via
+/// [`WidgetExt`].
+///
+/// What counts as a request for a context menu follows the conventions of the
+/// platform: a right click, or a control-click on macOS, and the context menu
+/// key or shift+F10 while the child has focus. On Windows, the closure is
+/// called when the right button is released over the child; elsewhere, it is
+/// called as soon as the button is pressed.
+///
+/// The closure receives the position the menu should be shown at, in the
+/// child's coordinate space, which is the center of the child for keyboard
+/// requests.
+///
+/// Mouse events that request a context menu are not passed on to the child, so
+/// a control-click on macOS is not also treated as a regular click.
+///
+/// [`on_context_click`]: super::WidgetExt::on_context_click
+/// [`WidgetExt`]: super::WidgetExt
+pub struct ContextClick<T> {
+    action: Box<dyn Fn(&mut EventCtx, &mut T, &Env, Point)>,
+}
+
+impl<T: Data> ContextClick<T> {
+    /// Create a new `ContextClick` [`Controller`].
+    pub fn new(action: impl Fn(&mut EventCtx, &mut T, &Env, Point) + 'static) -> Self {
+        ContextClick {
+            action: Box::new(action),
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for ContextClick<T> {
+    #[instrument(
+        name = "ContextClick",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.is_context_click() => {
+                if !ctx.is_disabled() {
+                    if OPEN_ON_RELEASE {
+                        ctx.set_active(true);
+                    } else {
+                        trace!("Context menu requested on {:?}", ctx.widget_id());
+                        (self.action)(ctx, data, env, mouse.pos);
+                    }
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseUp(mouse) if mouse.is_context_click() && OPEN_ON_RELEASE => {
+                if ctx.is_active() {
+                    ctx.set_active(false);
+                    if ctx.is_hot() && !ctx.is_disabled() {
+                        trace!("Context menu requested on {:?}", ctx.widget_id());
+                        (self.action)(ctx, data, env, mouse.pos);
+                    }
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::KeyDown(key) if ctx.has_focus() && !ctx.is_disabled() => {
+                let shift_f10 = key.key == KbKey::F10 && key.mods.shift();
+                if key.key == KbKey::ContextMenu || shift_f10 {
+                    let center = ctx.size().to_rect().center();
+                    (self.action)(ctx, data, env, center);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{SizedBox, WidgetExt};
+    use crate::{MouseButton, MouseButtons, MouseEvent};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    #[test]
+    fn right_click_opens_context_menu() {
+        let opened = Rc::new(Cell::new(None));
+        let opened_clone = opened.clone();
+        let clicked = Rc::new(Cell::new(false));
+        let clicked_clone = clicked.clone();
+        let widget = SizedBox::empty()
+            .width(100.)
+            .height(100.)
+            .on_click(move |_, _, _| clicked_clone.set(true))
+            .on_context_click(move |_, _, _, pos| opened_clone.set(Some(pos)));
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let right = |mouse: MouseEvent| MouseEvent {
+                button: MouseButton::Right,
+                buttons: MouseButtons::new().with(MouseButton::Right),
+                ..mouse
+            };
+            harness.event(Event::MouseMove(move_mouse((20., 30.))));
+            harness.event(Event::MouseDown(right(move_mouse((20., 30.)))));
+            harness.event(Event::MouseUp(MouseEvent {
+                buttons: MouseButtons::new(),
+                ..right(move_mouse((20., 30.)))
+            }));
+            assert_eq!(opened.get(), Some(Point::new(20., 30.)));
+            assert!(!clicked.get());
+        });
+    }
+}
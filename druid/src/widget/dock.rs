@@ -0,0 +1,816 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container with panels that can be docked to its sides or floated.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tracing::{instrument, trace};
+
+use crate::contexts::RequestCtx;
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{
+    commands, theme, Cursor, Lens, LensExt, MouseButton, Point, Rect, Selector, TextLayout,
+    WidgetPod, WindowConfig, WindowId,
+};
+
+/// The height of the title bar of a docked panel.
+const TITLE_HEIGHT: f64 = 22.0;
+/// The width of the gap between a dock and the center, used to resize the dock.
+const SPLITTER_WIDTH: f64 = 4.0;
+/// The smallest width or height of a dock.
+const MIN_DOCK_SIZE: f64 = 40.0;
+/// The part of the width or height of the area, at each edge, where a
+/// dragged panel is docked to that edge.
+const DOCK_ZONE: f64 = 0.25;
+/// How far a title bar needs to be dragged before the panel starts moving.
+const DRAG_THRESHOLD: f64 = 4.0;
+/// The size of the window of a floating panel.
+const FLOAT_SIZE: Size = Size::new(320.0, 240.0);
+
+/// Sent to the dock area by a floating panel when its window is closed.
+const PANEL_CLOSED: Selector<usize> = Selector::new("druid-builtin.dock.panel-closed");
+
+type PanelFactory<T> = Box<dyn Fn() -> Box<dyn Widget<T>>>;
+
+/// Where a panel of a [`DockManager`] is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Data)]
+pub enum DockSide {
+    /// Docked to the left edge.
+    Left,
+    /// Docked to the right edge.
+    Right,
+    /// Docked to the bottom edge, between the left and the right docks.
+    Bottom,
+    /// In a window of its own.
+    Floating,
+}
+
+/// The arrangement of the panels of a [`DockManager`].
+///
+/// This is part of the application data, so that it can be saved and
+/// restored: it is written as text by its `Display` implementation, and
+/// read back with [`str::parse`].
+#[derive(Debug, Clone, PartialEq, Data)]
+pub struct DockLayout {
+    /// The sides of the panels that were moved, by key.
+    sides: Arc<Vec<(String, DockSide)>>,
+    /// The width of the left dock.
+    pub left_width: f64,
+    /// The width of the right dock.
+    pub right_width: f64,
+    /// The height of the bottom dock.
+    pub bottom_height: f64,
+}
+
+/// An error returned when parsing a [`DockLayout`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDockLayoutError {
+    line: String,
+}
+
+/// A container with a central widget, surrounded by panels that can be
+/// docked to its left, right or bottom edge, or floated in windows of their
+/// own.
+///
+/// Each panel has a title bar. Dragging it to an edge of the container docks
+/// the panel there, and dropping it anywhere else floats it in a new window;
+/// closing that window docks the panel again, on its default side. The gaps
+/// between the docks and the center can be dragged to resize the docks.
+///
+/// Where the panels are is kept in a [`DockLayout`] in the application data,
+/// so that the arrangement of a workspace can be saved and restored.
+pub struct DockManager<T, L> {
+    // Floating panels need a pod of their own to sync with, like the popups
+    // in the `popup` module.
+    inner: WidgetPod<T, DockArea<T, L>>,
+}
+
+struct DockArea<T, L> {
+    layout: L,
+    center: WidgetPod<T, Box<dyn Widget<T>>>,
+    panels: Vec<Panel<T>>,
+    /// The docks that have panels, and where.
+    docks: Vec<(DockSide, Rect)>,
+    drag: Option<Drag>,
+    hot_splitter: Option<DockSide>,
+    /// Where to open the window of the next panel that is floated.
+    float_at: Option<Point>,
+}
+
+struct Panel<T> {
+    key: String,
+    title: TextLayout<String>,
+    default_side: DockSide,
+    make: PanelFactory<T>,
+    /// The contents of the panel while it is docked.
+    content: Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    /// The window of the panel while it is floating.
+    window: Option<WindowId>,
+    title_rect: Rect,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    Panel {
+        idx: usize,
+        start: Point,
+        pos: Point,
+        moved: bool,
+    },
+    Splitter(DockSide),
+}
+
+/// The root of the window of a floating panel.
+struct FloatingPanel<T> {
+    content: WidgetPod<T, Box<dyn Widget<T>>>,
+    /// The dock area the panel belongs to.
+    area: WidgetId,
+    panel: usize,
+}
+
+impl DockSide {
+    fn name(self) -> &'static str {
+        match self {
+            DockSide::Left => "left",
+            DockSide::Right => "right",
+            DockSide::Bottom => "bottom",
+            DockSide::Floating => "floating",
+        }
+    }
+}
+
+impl DockLayout {
+    /// Create a layout with every panel on its default side.
+    pub fn new() -> Self {
+        DockLayout {
+            sides: Arc::new(Vec::new()),
+            left_width: 240.0,
+            right_width: 240.0,
+            bottom_height: 160.0,
+        }
+    }
+
+    /// The side the panel with the given key was moved to, if it was moved.
+    pub fn side(&self, key: &str) -> Option<DockSide> {
+        self.sides
+            .iter()
+            .find(|(panel, _)| panel == key)
+            .map(|(_, side)| *side)
+    }
+
+    /// Move the panel with the given key to `side`.
+    pub fn set_side(&mut self, key: &str, side: DockSide) {
+        let sides = Arc::make_mut(&mut self.sides);
+        match sides.iter_mut().find(|(panel, _)| panel == key) {
+            Some(entry) => entry.1 = side,
+            None => sides.push((key.to_string(), side)),
+        }
+    }
+}
+
+impl Default for DockLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for DockLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "left_width {}", self.left_width)?;
+        writeln!(f, "right_width {}", self.right_width)?;
+        writeln!(f, "bottom_height {}", self.bottom_height)?;
+        for (key, side) in self.sides.iter() {
+            writeln!(f, "panel {} {}", side.name(), key)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for DockLayout {
+    type Err = ParseDockLayoutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut layout = DockLayout::new();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let error = || ParseDockLayoutError {
+                line: line.to_string(),
+            };
+            let (name, value) = line.split_once(' ').ok_or_else(error)?;
+            let value = value.trim();
+            match name {
+                "left_width" => layout.left_width = value.parse().map_err(|_| error())?,
+                "right_width" => layout.right_width = value.parse().map_err(|_| error())?,
+                "bottom_height" => layout.bottom_height = value.parse().map_err(|_| error())?,
+                "panel" => {
+                    let (side, key) = value.split_once(' ').ok_or_else(error)?;
+                    let side = match side {
+                        "left" => DockSide::Left,
+                        "right" => DockSide::Right,
+                        "bottom" => DockSide::Bottom,
+                        "floating" => DockSide::Floating,
+                        _ => return Err(error()),
+                    };
+                    layout.set_side(key.trim(), side);
+                }
+                _ => return Err(error()),
+            }
+        }
+        Ok(layout)
+    }
+}
+
+impl fmt::Display for ParseDockLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid dock layout line '{}'", self.line)
+    }
+}
+
+impl std::error::Error for ParseDockLayoutError {}
+
+impl<T: Data, L: Lens<T, DockLayout> + 'static> DockManager<T, L> {
+    /// Create a new `DockManager` around `center`, keeping the arrangement
+    /// of its panels in the [`DockLayout`] that `layout` points to.
+    pub fn new(layout: L, center: impl Widget<T> + 'static) -> Self {
+        DockManager {
+            inner: WidgetPod::new(DockArea {
+                layout,
+                center: WidgetPod::new(center).boxed(),
+                panels: Vec::new(),
+                docks: Vec::new(),
+                drag: None,
+                hot_splitter: None,
+                float_at: None,
+            }),
+        }
+    }
+
+    /// Builder-style method to add a panel.
+    ///
+    /// The `key` identifies the panel in the [`DockLayout`], and should stay
+    /// the same between runs of the application. The panel is shown on
+    /// `default_side` until it is moved. Its contents are created with
+    /// `make`, which is called again each time the panel is docked or floated.
+    pub fn with_panel<W: Widget<T> + 'static>(
+        mut self,
+        key: impl Into<String>,
+        title: impl Into<String>,
+        default_side: DockSide,
+        make: impl Fn() -> W + 'static,
+    ) -> Self {
+        self.inner.widget_mut().panels.push(Panel {
+            key: key.into(),
+            title: TextLayout::from_text(title.into()),
+            default_side,
+            make: Box::new(move || Box::new(make())),
+            content: None,
+            window: None,
+            title_rect: Rect::ZERO,
+        });
+        self
+    }
+}
+
+/// Where a panel dropped at `pos`, in an area of `size`, is moved to.
+fn drop_target(size: Size, pos: Point) -> DockSide {
+    if pos.x < size.width * DOCK_ZONE {
+        DockSide::Left
+    } else if pos.x > size.width * (1.0 - DOCK_ZONE) {
+        DockSide::Right
+    } else if pos.y > size.height * (1.0 - DOCK_ZONE) {
+        DockSide::Bottom
+    } else {
+        DockSide::Floating
+    }
+}
+
+impl<T: Data, L: Lens<T, DockLayout>> DockArea<T, L> {
+    fn side(&self, panel: &Panel<T>, data: &T) -> DockSide {
+        self.layout
+            .with(data, |layout| layout.side(&panel.key))
+            .unwrap_or(panel.default_side)
+    }
+
+    fn dock_rect(&self, side: DockSide) -> Option<Rect> {
+        self.docks
+            .iter()
+            .find(|(dock, _)| *dock == side)
+            .map(|(_, rect)| *rect)
+    }
+
+    /// The gap between the given dock and the center.
+    fn splitter_rect(&self, side: DockSide) -> Option<Rect> {
+        let dock = self.dock_rect(side)?;
+        Some(match side {
+            DockSide::Left => Rect::new(dock.x1, dock.y0, dock.x1 + SPLITTER_WIDTH, dock.y1),
+            DockSide::Right => Rect::new(dock.x0 - SPLITTER_WIDTH, dock.y0, dock.x0, dock.y1),
+            DockSide::Bottom => Rect::new(dock.x0, dock.y0 - SPLITTER_WIDTH, dock.x1, dock.y0),
+            DockSide::Floating => return None,
+        })
+    }
+
+    fn splitter_at(&self, pos: Point) -> Option<DockSide> {
+        [DockSide::Left, DockSide::Right, DockSide::Bottom]
+            .into_iter()
+            .find(|side| {
+                self.splitter_rect(*side)
+                    .map_or(false, |rect| rect.contains(pos))
+            })
+    }
+
+    /// Create the contents of docked panels, and open or close the windows of
+    /// floating ones, to match the layout.
+    fn sync_panels(&mut self, ctx: &mut impl RequestCtx, area: WidgetId, data: &T, env: &Env) {
+        let mut children_changed = false;
+        for idx in 0..self.panels.len() {
+            let floating = self.side(&self.panels[idx], data) == DockSide::Floating;
+            let panel = &mut self.panels[idx];
+            if floating == panel.content.is_some() {
+                panel.content = (!floating).then(|| WidgetPod::new((panel.make)()));
+                children_changed = true;
+            }
+            if floating && panel.window.is_none() {
+                trace!("Floating panel {:?}", panel.key);
+                let mut config = WindowConfig::default().window_size(FLOAT_SIZE);
+                if let Some(position) = self.float_at.take() {
+                    config = config.set_position(position);
+                }
+                let window = FloatingPanel {
+                    content: WidgetPod::new((panel.make)()),
+                    area,
+                    panel: idx,
+                };
+                panel.window = Some(ctx.new_sub_window(config, window, data.clone(), env.clone()));
+            } else if !floating {
+                if let Some(window) = panel.window.take() {
+                    ctx.submit_command(commands::CLOSE_WINDOW.to(window));
+                }
+            }
+        }
+        if children_changed {
+            ctx.children_changed();
+            ctx.request_layout();
+        }
+    }
+}
+
+impl<T: Data, L: Lens<T, DockLayout> + 'static> Widget<T> for DockManager<T, L> {
+    #[instrument(
+        name = "DockManager",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "DockManager",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "DockManager",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    #[instrument(name = "DockManager", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "DockManager", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.widget().debug_state(data)
+    }
+}
+
+impl<T: Data, L: Lens<T, DockLayout>> Widget<T> for DockArea<T, L> {
+    #[instrument(name = "DockArea", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::WindowConnected => {
+                let area = ctx.widget_id();
+                self.sync_panels(ctx, area, data, env);
+            }
+            Event::Command(cmd) if cmd.is(PANEL_CLOSED) => {
+                let idx = *cmd.get_unchecked(PANEL_CLOSED);
+                if let Some(panel) = self.panels.get_mut(idx) {
+                    panel.window = None;
+                    if self.side(&self.panels[idx], data) == DockSide::Floating {
+                        let panel = &self.panels[idx];
+                        let (key, side) = (panel.key.clone(), panel.default_side);
+                        self.layout
+                            .with_mut(data, |layout| layout.set_side(&key, side));
+                    }
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                let title = self.panels.iter().position(|panel| {
+                    panel.content.is_some() && panel.title_rect.contains(mouse.pos)
+                });
+                if let Some(side) = self.splitter_at(mouse.pos) {
+                    self.drag = Some(Drag::Splitter(side));
+                } else if let Some(idx) = title {
+                    self.drag = Some(Drag::Panel {
+                        idx,
+                        start: mouse.pos,
+                        pos: mouse.pos,
+                        moved: false,
+                    });
+                }
+                if self.drag.is_some() {
+                    ctx.set_active(true);
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                match self.drag.as_mut() {
+                    Some(Drag::Splitter(side)) => {
+                        let size = ctx.size();
+                        let side = *side;
+                        self.layout.with_mut(data, |layout| match side {
+                            DockSide::Left => layout.left_width = mouse.pos.x,
+                            DockSide::Right => layout.right_width = size.width - mouse.pos.x,
+                            DockSide::Bottom => layout.bottom_height = size.height - mouse.pos.y,
+                            DockSide::Floating => (),
+                        });
+                        ctx.request_layout();
+                    }
+                    Some(Drag::Panel {
+                        start, pos, moved, ..
+                    }) => {
+                        *pos = mouse.pos;
+                        *moved |= (mouse.pos - *start).hypot() > DRAG_THRESHOLD;
+                        ctx.request_paint();
+                    }
+                    None => (),
+                }
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                let hot_splitter = self.splitter_at(mouse.pos);
+                if hot_splitter != self.hot_splitter {
+                    self.hot_splitter = hot_splitter;
+                    match hot_splitter {
+                        Some(DockSide::Bottom) => ctx.set_cursor(&Cursor::ResizeUpDown),
+                        Some(_) => ctx.set_cursor(&Cursor::ResizeLeftRight),
+                        None => ctx.clear_cursor(),
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && ctx.is_active() => {
+                ctx.set_active(false);
+                if let Some(Drag::Panel {
+                    idx,
+                    pos,
+                    moved: true,
+                    ..
+                }) = self.drag
+                {
+                    let side = drop_target(ctx.size(), pos);
+                    trace!("Dropping panel {:?} on {:?}", self.panels[idx].key, side);
+                    if side == DockSide::Floating {
+                        self.float_at = Some(ctx.to_screen(pos));
+                    }
+                    let key = self.panels[idx].key.clone();
+                    self.layout
+                        .with_mut(data, |layout| layout.set_side(&key, side));
+                    ctx.request_paint();
+                }
+                self.drag = None;
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        self.center.event(ctx, event, data, env);
+        for panel in &mut self.panels {
+            if let Some(content) = panel.content.as_mut() {
+                content.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    #[instrument(name = "DockArea", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            for idx in 0..self.panels.len() {
+                if self.side(&self.panels[idx], data) != DockSide::Floating {
+                    let panel = &mut self.panels[idx];
+                    panel.content = Some(WidgetPod::new((panel.make)()));
+                }
+            }
+        }
+        self.center.lifecycle(ctx, event, data, env);
+        for panel in &mut self.panels {
+            if let Some(content) = panel.content.as_mut() {
+                content.lifecycle(ctx, event, data, env);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "DockArea",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let old_layout = self.layout.get(old_data);
+        let layout = self.layout.get(data);
+        if !old_layout.same(&layout) {
+            let area = ctx.widget_id();
+            self.sync_panels(ctx, area, data, env);
+            ctx.request_layout();
+        }
+        for panel in &mut self.panels {
+            if panel.title.needs_rebuild_after_update(ctx) {
+                ctx.request_layout();
+            }
+            if let Some(content) = panel.content.as_mut() {
+                content.update(ctx, data, env);
+            }
+        }
+        self.center.update(ctx, data, env);
+    }
+
+    #[instrument(name = "DockArea", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("DockManager");
+        let size = bc.max();
+        let layout = self.layout.get(data);
+        let has_panels = |side| {
+            self.panels
+                .iter()
+                .any(|panel| panel.content.is_some() && self.side(panel, data) == side)
+        };
+        let max_dock = |available: f64| (available / 2.0).max(MIN_DOCK_SIZE);
+        let left = has_panels(DockSide::Left)
+            .then(|| layout.left_width.clamp(MIN_DOCK_SIZE, max_dock(size.width)));
+        let right = has_panels(DockSide::Right).then(|| {
+            layout
+                .right_width
+                .clamp(MIN_DOCK_SIZE, max_dock(size.width))
+        });
+        let bottom = has_panels(DockSide::Bottom).then(|| {
+            layout
+                .bottom_height
+                .clamp(MIN_DOCK_SIZE, max_dock(size.height))
+        });
+
+        self.docks.clear();
+        let mut center = size.to_rect();
+        if let Some(width) = left {
+            self.docks
+                .push((DockSide::Left, Rect::new(0.0, 0.0, width, size.height)));
+            center.x0 = width + SPLITTER_WIDTH;
+        }
+        if let Some(width) = right {
+            let x = size.width - width;
+            self.docks
+                .push((DockSide::Right, Rect::new(x, 0.0, size.width, size.height)));
+            center.x1 = x - SPLITTER_WIDTH;
+        }
+        if let Some(height) = bottom {
+            let y = size.height - height;
+            self.docks.push((
+                DockSide::Bottom,
+                Rect::new(center.x0, y, center.x1, size.height),
+            ));
+            center.y1 = y - SPLITTER_WIDTH;
+        }
+        let center = Rect::from_origin_size(center.origin(), center.size().clamp(Size::ZERO, size));
+        self.center
+            .layout(ctx, &BoxConstraints::tight(center.size()), data, env);
+        self.center.set_origin(ctx, center.origin());
+
+        for (side, dock) in self.docks.clone() {
+            let members: Vec<usize> = (0..self.panels.len())
+                .filter(|idx| {
+                    let panel = &self.panels[*idx];
+                    panel.content.is_some() && self.side(panel, data) == side
+                })
+                .collect();
+            // The panels of a dock share its space equally.
+            let count = members.len() as f64;
+            for (n, idx) in members.into_iter().enumerate() {
+                let n = n as f64;
+                let slot = if side == DockSide::Bottom {
+                    let width = dock.width() / count;
+                    Rect::new(
+                        dock.x0 + width * n,
+                        dock.y0,
+                        dock.x0 + width * (n + 1.0),
+                        dock.y1,
+                    )
+                } else {
+                    let height = dock.height() / count;
+                    Rect::new(
+                        dock.x0,
+                        dock.y0 + height * n,
+                        dock.x1,
+                        dock.y0 + height * (n + 1.0),
+                    )
+                };
+                let panel = &mut self.panels[idx];
+                panel.title.rebuild_if_needed(ctx.text(), env);
+                panel.title_rect = slot.with_size((slot.width(), TITLE_HEIGHT));
+                if let Some(content) = panel.content.as_mut() {
+                    let content_size =
+                        Size::new(slot.width(), (slot.height() - TITLE_HEIGHT).max(0.0));
+                    content.layout(ctx, &BoxConstraints::tight(content_size), data, env);
+                    content.set_origin(ctx, Point::new(slot.x0, slot.y0 + TITLE_HEIGHT));
+                }
+            }
+        }
+
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "DockArea", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.center.paint(ctx, data, env);
+        for side in [DockSide::Left, DockSide::Right, DockSide::Bottom] {
+            if let Some(splitter) = self.splitter_rect(side) {
+                ctx.fill(splitter, &env.get(theme::BORDER_DARK));
+            }
+        }
+        for panel in &mut self.panels {
+            if let Some(content) = panel.content.as_mut() {
+                ctx.fill(panel.title_rect, &env.get(theme::BACKGROUND_LIGHT));
+                let y = panel.title_rect.y0 + (TITLE_HEIGHT - panel.title.size().height) / 2.0;
+                panel
+                    .title
+                    .draw(ctx, (panel.title_rect.x0 + SPLITTER_WIDTH * 2.0, y));
+                content.paint(ctx, data, env);
+            }
+        }
+
+        if let Some(Drag::Panel {
+            pos, moved: true, ..
+        }) = self.drag
+        {
+            let size = ctx.size();
+            let preview = match drop_target(size, pos) {
+                DockSide::Left => Rect::new(0.0, 0.0, size.width * DOCK_ZONE, size.height),
+                DockSide::Right => {
+                    Rect::new(size.width * (1.0 - DOCK_ZONE), 0.0, size.width, size.height)
+                }
+                DockSide::Bottom => Rect::new(
+                    0.0,
+                    size.height * (1.0 - DOCK_ZONE),
+                    size.width,
+                    size.height,
+                ),
+                DockSide::Floating => Rect::from_origin_size(pos, FLOAT_SIZE),
+            };
+            let color = env.get(theme::PRIMARY_LIGHT);
+            ctx.fill(preview, &color.clone().with_alpha(0.25));
+            ctx.stroke(preview.inset(-0.5), &color, 1.0);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut children = vec![self.center.widget().debug_state(data)];
+        children.extend(
+            self.panels
+                .iter()
+                .filter_map(|panel| panel.content.as_ref())
+                .map(|content| content.widget().debug_state(data)),
+        );
+        DebugState {
+            display_name: "DockManager".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for FloatingPanel<T> {
+    #[instrument(
+        name = "FloatingPanel",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::WindowDisconnected = event {
+            ctx.submit_command(PANEL_CLOSED.with(self.panel).to(self.area));
+        }
+        self.content.event(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "FloatingPanel",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.content.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "FloatingPanel",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.content.update(ctx, data, env);
+    }
+
+    #[instrument(
+        name = "FloatingPanel",
+        level = "trace",
+        skip(self, ctx, bc, data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.content.layout(ctx, bc, data, env);
+        self.content.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "FloatingPanel", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.content.paint(ctx, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lens::Identity;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{MouseButtons, MouseEvent};
+    use test_log::test;
+
+    #[test]
+    fn layout_round_trips_through_text() {
+        let mut layout = DockLayout::new();
+        layout.left_width = 180.0;
+        layout.set_side("files", DockSide::Right);
+        layout.set_side("search results", DockSide::Floating);
+        let text = layout.to_string();
+        assert_eq!(text.parse::<DockLayout>(), Ok(layout));
+        assert!("panel sideways files".parse::<DockLayout>().is_err());
+    }
+
+    #[test]
+    fn dragging_a_title_bar_moves_the_panel() {
+        let manager = DockManager::new(Identity, SizedBox::empty()).with_panel(
+            "files",
+            "Files",
+            DockSide::Left,
+            SizedBox::empty,
+        );
+
+        Harness::create_simple(DockLayout::new(), manager, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let mouse = |x, y| MouseEvent {
+                button: MouseButton::Left,
+                buttons: MouseButtons::new().with(MouseButton::Left),
+                ..move_mouse((x, y))
+            };
+            harness.event(Event::MouseMove(mouse(20., 10.)));
+            harness.event(Event::MouseDown(mouse(20., 10.)));
+            harness.event(Event::MouseMove(mouse(380., 200.)));
+            harness.event(Event::MouseUp(mouse(380., 200.)));
+            assert_eq!(harness.data().side("files"), Some(DockSide::Right));
+        });
+    }
+}
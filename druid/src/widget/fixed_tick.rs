@@ -0,0 +1,190 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that advances app state at a fixed rate.
+
+use std::time::Duration;
+
+// Automatically defaults to std::time::Instant on non Wasm platforms
+use instant::Instant;
+use tracing::{instrument, trace};
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, TimerToken, Widget};
+
+/// The most steps that are run for a single timer; any time beyond that is dropped.
+const MAX_CATCH_UP_STEPS: u32 = 5;
+
+/// A [`Controller`] that calls a closure at a fixed rate, independently of painting.
+///
+/// This is intended for simulation-style apps, such as games, physics demos or
+/// audio meters, that want to advance their state in steps of constant length.
+/// The closure receives the total simulated time so far, which grows by exactly
+/// `step` between calls.
+///
+/// Timers are not precise, so the controller measures how much time has really
+/// passed and runs as many steps as fit into it. If the app falls far behind,
+/// for instance because the machine was asleep, the excess time is dropped
+/// rather than replayed all at once.
+///
+/// Ticking starts when the widget is added to the tree.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{FixedTick, Label, WidgetExt};
+///
+/// let tick = FixedTick::new(Duration::from_millis(16), |_ctx, pos: &mut f64, _env, _time| {
+///     *pos += 0.5;
+/// });
+/// let ball = Label::new(|pos: &f64, _env: &_| format!("{:.1}", pos)).controller(tick);
+/// ```
+pub struct FixedTick<T> {
+    step: Duration,
+    action: Box<dyn Fn(&mut EventCtx, &mut T, &Env, Duration)>,
+    timer: TimerToken,
+    last_tick: Option<Instant>,
+    /// Real time that has passed but not been simulated yet.
+    pending: Duration,
+    simulated: Duration,
+}
+
+impl<T: Data> FixedTick<T> {
+    /// Create a new `FixedTick` that calls `action` every `step`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero.
+    pub fn new(
+        step: Duration,
+        action: impl Fn(&mut EventCtx, &mut T, &Env, Duration) + 'static,
+    ) -> Self {
+        assert!(step > Duration::ZERO, "FixedTick step must not be zero");
+        FixedTick {
+            step,
+            action: Box::new(action),
+            timer: TimerToken::INVALID,
+            last_tick: None,
+            pending: Duration::ZERO,
+            simulated: Duration::ZERO,
+        }
+    }
+}
+
+/// Add `elapsed` to `pending` and return how many whole steps should be run.
+fn take_steps(pending: &mut Duration, step: Duration, elapsed: Duration) -> u32 {
+    *pending += elapsed;
+    let mut steps = 0;
+    while *pending >= step && steps < MAX_CATCH_UP_STEPS {
+        *pending -= step;
+        steps += 1;
+    }
+    if *pending >= step {
+        trace!("FixedTick fell behind, dropping {:?}", *pending);
+        *pending = Duration::ZERO;
+    }
+    steps
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for FixedTick<T> {
+    #[instrument(
+        name = "FixedTick",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                let now = ctx.now();
+                let elapsed = self
+                    .last_tick
+                    .map_or(self.step, |last| now.duration_since(last));
+                self.last_tick = Some(now);
+                for _ in 0..take_steps(&mut self.pending, self.step, elapsed) {
+                    self.simulated += self.step;
+                    (self.action)(ctx, data, env, self.simulated);
+                }
+                self.timer = ctx.request_timer(self.step);
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    #[instrument(
+        name = "FixedTick",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.last_tick = Some(ctx.now());
+            self.timer = ctx.request_timer(self.step);
+        }
+
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::{SizedBox, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn steps_carry_remainder() {
+        let step = Duration::from_millis(10);
+        let mut pending = Duration::ZERO;
+        assert_eq!(take_steps(&mut pending, step, Duration::from_millis(7)), 0);
+        assert_eq!(take_steps(&mut pending, step, Duration::from_millis(7)), 1);
+        assert_eq!(pending, Duration::from_millis(4));
+        assert_eq!(take_steps(&mut pending, step, Duration::from_millis(26)), 3);
+        assert_eq!(pending, Duration::ZERO);
+    }
+
+    #[test]
+    fn excess_time_is_dropped() {
+        let step = Duration::from_millis(10);
+        let mut pending = Duration::ZERO;
+        let steps = take_steps(&mut pending, step, Duration::from_secs(10));
+        assert_eq!(steps, MAX_CATCH_UP_STEPS);
+        assert_eq!(pending, Duration::ZERO);
+    }
+
+    #[test]
+    fn steps_follow_the_window_clock() {
+        let tick = FixedTick::new(Duration::from_millis(10), |_, count: &mut u32, _, time| {
+            *count += 1;
+            assert_eq!(time, Duration::from_millis(10) * *count);
+        });
+        let widget = SizedBox::empty().expand().controller(tick);
+
+        Harness::create_simple(0, widget, |harness| {
+            harness.send_initial_events();
+            harness.advance(Duration::from_millis(95));
+            assert_eq!(*harness.data(), 9);
+            harness.advance(Duration::from_millis(5));
+            assert_eq!(*harness.data(), 10);
+        });
+    }
+}
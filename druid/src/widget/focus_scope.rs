@@ -0,0 +1,271 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that keeps keyboard focus traversal within a region of a window.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{KbKey, Point, Selector, Target, WidgetPod};
+
+/// Sent to the whole window to find the scope to move focus to.
+const COLLECT_SCOPES: Selector<Rc<RefCell<ScopeCycle>>> =
+    Selector::new("druid-builtin.focus-scope.collect-scopes");
+/// Sent to the scope that started a cycle, once all scopes were collected.
+const FINISH_CYCLE: Selector<Rc<RefCell<ScopeCycle>>> =
+    Selector::new("druid-builtin.focus-scope.finish-cycle");
+
+/// A region of a window, such as a sidebar or an editor, with its own order of
+/// keyboard focus.
+///
+/// While focus is inside a `FocusScope`, moving focus to the next or previous
+/// widget, as tab and shift+tab do in text boxes, wraps around within the
+/// scope instead of leaving it. Pressing F6 moves focus to the next scope in
+/// the window, and shift+F6 to the previous one; each scope gets focus back
+/// on the widget that had it last.
+pub struct FocusScope<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    /// The focusable widgets in this scope, in order.
+    chain: Vec<WidgetId>,
+    last_focused: Option<WidgetId>,
+}
+
+/// The state of a move of focus between scopes.
+#[derive(Debug)]
+struct ScopeCycle {
+    source: WidgetId,
+    forward: bool,
+    seen_source: bool,
+    first: Option<WidgetId>,
+    last: Option<WidgetId>,
+    before_source: Option<WidgetId>,
+    after_source: Option<WidgetId>,
+}
+
+impl ScopeCycle {
+    /// Record the widget that would get focus in a scope, in window order.
+    fn add_scope(&mut self, scope: WidgetId, entry: WidgetId) {
+        if scope == self.source {
+            self.seen_source = true;
+        } else if !self.seen_source {
+            self.before_source = Some(entry);
+        } else if self.after_source.is_none() {
+            self.after_source = Some(entry);
+        }
+        self.first.get_or_insert(entry);
+        self.last = Some(entry);
+    }
+
+    /// The widget to move focus to.
+    fn target(&self) -> Option<WidgetId> {
+        if self.forward {
+            self.after_source.or(self.first)
+        } else {
+            self.before_source.or(self.last)
+        }
+    }
+}
+
+impl<T: Data> FocusScope<T> {
+    /// Create a new `FocusScope` around `child`.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        FocusScope {
+            child: WidgetPod::new(child).boxed(),
+            chain: Vec::new(),
+            last_focused: None,
+        }
+    }
+
+    /// The widget that gets focus when this scope is entered with F6.
+    fn entry(&self) -> Option<WidgetId> {
+        self.last_focused
+            .filter(|id| self.chain.contains(id))
+            .or_else(|| self.chain.first().copied())
+    }
+}
+
+impl<T: Data> Widget<T> for FocusScope<T> {
+    #[instrument(
+        name = "FocusScope",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(COLLECT_SCOPES) => {
+                if let Some(entry) = self.entry() {
+                    let cycle = cmd.get_unchecked(COLLECT_SCOPES);
+                    cycle.borrow_mut().add_scope(ctx.widget_id(), entry);
+                }
+            }
+            Event::Command(cmd) if cmd.is(FINISH_CYCLE) => {
+                let target = cmd.get_unchecked(FINISH_CYCLE).borrow().target();
+                if let Some(target) = target {
+                    trace!("Moving focus to scope with {:?}", target);
+                    ctx.set_focus(target);
+                }
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        self.child.event(ctx, event, data, env);
+
+        if !ctx.has_focus() {
+            return;
+        }
+        if let Some(focused) = ctx.focused_widget() {
+            if self.chain.contains(&focused) {
+                self.last_focused = Some(focused);
+            }
+        }
+        if let Some(forward) = ctx.take_focus_traversal() {
+            // Wrap around within the scope.
+            let len = self.chain.len();
+            let current = self
+                .last_focused
+                .and_then(|id| self.chain.iter().position(|c| *c == id));
+            let next = match (current, forward) {
+                (Some(idx), true) => (idx + 1) % len,
+                (Some(idx), false) => (idx + len - 1) % len,
+                (None, true) => 0,
+                (None, false) => len.saturating_sub(1),
+            };
+            if let Some(&target) = self.chain.get(next) {
+                ctx.set_focus(target);
+            }
+        }
+        if let Event::KeyDown(key) = event {
+            if key.key == KbKey::F6 && !ctx.is_handled() {
+                let cycle = Rc::new(RefCell::new(ScopeCycle {
+                    source: ctx.widget_id(),
+                    forward: !key.mods.shift(),
+                    seen_source: false,
+                    first: None,
+                    last: None,
+                    before_source: None,
+                    after_source: None,
+                }));
+                let window = Target::Window(ctx.window_id());
+                ctx.submit_command(COLLECT_SCOPES.with(cycle.clone()).to(window));
+                ctx.submit_command(FINISH_CYCLE.with(cycle).to(ctx.widget_id()));
+                ctx.set_handled();
+            }
+        }
+    }
+
+    #[instrument(
+        name = "FocusScope",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let chain_start = ctx.focus_chain().len();
+        self.child.lifecycle(ctx, event, data, env);
+        if let LifeCycle::BuildFocusChain = event {
+            self.chain = ctx.focus_chain()[chain_start..].to_vec();
+        }
+    }
+
+    #[instrument(
+        name = "FocusScope",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "FocusScope", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.paint_insets());
+        ctx.set_baseline_offset(self.child.baseline_offset());
+        size
+    }
+
+    #[instrument(name = "FocusScope", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::Flex;
+    use crate::{KeyEvent, Modifiers};
+    use test_log::test;
+
+    const TAKE_FOCUS: Selector = Selector::new("druid-tests.take-focus");
+
+    fn focusable(id: WidgetId) -> impl Widget<()> {
+        ModularWidget::new(())
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::BuildFocusChain = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .event_fn(|_, ctx, event, _, _| match event {
+                Event::KeyDown(key) if key.key == KbKey::Tab => ctx.focus_next(),
+                Event::Command(cmd) if cmd.is(TAKE_FOCUS) => ctx.request_focus(),
+                _ => (),
+            })
+            .with_id(id)
+    }
+
+    #[test]
+    fn tab_wraps_and_f6_cycles_scopes() {
+        let [a1, a2, b1] = widget_ids();
+        let sidebar = Flex::column()
+            .with_child(focusable(a1))
+            .with_child(focusable(a2));
+        let sidebar = FocusScope::new(sidebar);
+        let editor = FocusScope::new(focusable(b1));
+        let root = Flex::row().with_child(sidebar).with_child(editor);
+        let key = |key| Event::KeyDown(KeyEvent::for_test(Modifiers::default(), key));
+
+        Harness::create_simple((), root, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(TAKE_FOCUS.to(a1));
+            harness.event(key(KbKey::Tab));
+            assert_eq!(harness.window().focus, Some(a2));
+            // Tab stays within the sidebar.
+            harness.event(key(KbKey::Tab));
+            assert_eq!(harness.window().focus, Some(a1));
+
+            harness.event(key(KbKey::F6));
+            assert_eq!(harness.window().focus, Some(b1));
+            // Coming back to the sidebar focuses the widget that had focus last.
+            harness.event(key(KbKey::F6));
+            assert_eq!(harness.window().focus, Some(a1));
+        });
+    }
+}
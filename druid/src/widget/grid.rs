@@ -0,0 +1,601 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that lays out its children in rows and columns.
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{theme, KeyOrValue, Point, Rect, WidgetPod};
+
+/// A container that places its children in the cells of a grid.
+///
+/// The grid is made of column and row tracks, each with a [`GridTrack`] size.
+/// Every child goes into a cell, given by [`GridParams`], and can span several
+/// tracks in either direction. Children referring to tracks past the ones
+/// that were declared get extra [`GridTrack::Auto`] tracks.
+///
+/// # Examples
+///
+/// A form, with labels sized to the widest one and text boxes taking the rest
+/// of the width:
+///
+/// ```
+/// use druid::widget::{Button, Grid, GridAlignment, GridParams, GridTrack, Label, TextBox};
+/// use druid::WidgetExt;
+///
+/// let label_params = |row| GridParams::new(0, row)
+///     .with_alignment(GridAlignment::End, GridAlignment::Center);
+/// let form = Grid::<(String, String)>::new()
+///     .with_column(GridTrack::Auto)
+///     .with_column(GridTrack::Flex(1.0))
+///     .with_child(Label::new("Name"), label_params(0))
+///     .with_child(TextBox::new().lens(druid::lens!((String, String), 0)), (1, 0))
+///     .with_child(Label::new("Email"), label_params(1))
+///     .with_child(TextBox::new().lens(druid::lens!((String, String), 1)), (1, 1))
+///     .with_child(
+///         Button::new("Submit"),
+///         GridParams::new(0, 2)
+///             .with_span(2, 1)
+///             .with_alignment(GridAlignment::End, GridAlignment::Start),
+///     );
+/// ```
+pub struct Grid<T> {
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    column_spacing: KeyOrValue<f64>,
+    row_spacing: KeyOrValue<f64>,
+    children: Vec<GridChild<T>>,
+}
+
+/// The size of a row or column of a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    /// A track of a fixed size.
+    Fixed(f64),
+    /// A track sized to fit the children in it.
+    Auto,
+    /// A track that takes a share of the space left over by the other tracks,
+    /// in proportion to its flex factor.
+    ///
+    /// When the grid has unbounded space in this direction, the track is
+    /// sized like [`GridTrack::Auto`].
+    Flex(f64),
+}
+
+/// How a child is placed inside its cell, along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridAlignment {
+    /// The child keeps its own size, at the start of the cell.
+    Start,
+    /// The child keeps its own size, in the middle of the cell.
+    Center,
+    /// The child keeps its own size, at the end of the cell.
+    End,
+    /// The child is stretched to fill the cell.
+    Fill,
+}
+
+/// The cell of a child in a [`Grid`], and how the child is placed in it.
+///
+/// A `(column, row)` tuple converts into `GridParams` for a child in a single
+/// cell, filling it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridParams {
+    column: usize,
+    row: usize,
+    column_span: usize,
+    row_span: usize,
+    horizontal: GridAlignment,
+    vertical: GridAlignment,
+}
+
+struct GridChild<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    params: GridParams,
+    /// The constraints and size of the child's last layout in the current pass.
+    last_layout: Option<(BoxConstraints, Size)>,
+}
+
+impl<T: Data> GridChild<T> {
+    /// Lay out the child, reusing its size if it was already laid out with the
+    /// same constraints during this layout pass.
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        if let Some((last_bc, size)) = self.last_layout {
+            if last_bc == *bc {
+                return size;
+            }
+        }
+        let size = self.widget.layout(ctx, bc, data, env);
+        self.last_layout = Some((*bc, size));
+        size
+    }
+}
+
+impl GridParams {
+    /// Place a child in the cell at `column` and `row`, counted from zero.
+    pub fn new(column: usize, row: usize) -> Self {
+        GridParams {
+            column,
+            row,
+            column_span: 1,
+            row_span: 1,
+            horizontal: GridAlignment::Fill,
+            vertical: GridAlignment::Fill,
+        }
+    }
+
+    /// Builder-style method to make the child span several columns and rows.
+    ///
+    /// Spans of zero are treated as one.
+    pub fn with_span(mut self, columns: usize, rows: usize) -> Self {
+        self.column_span = columns.max(1);
+        self.row_span = rows.max(1);
+        self
+    }
+
+    /// Builder-style method to set how the child is placed in its cell.
+    ///
+    /// The default is [`GridAlignment::Fill`] in both directions.
+    pub fn with_alignment(mut self, horizontal: GridAlignment, vertical: GridAlignment) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+}
+
+impl From<(usize, usize)> for GridParams {
+    fn from((column, row): (usize, usize)) -> Self {
+        GridParams::new(column, row)
+    }
+}
+
+impl GridAlignment {
+    /// The offset of a child of size `child` in a cell of size `cell`.
+    fn offset(self, child: f64, cell: f64) -> f64 {
+        match self {
+            GridAlignment::Start | GridAlignment::Fill => 0.0,
+            GridAlignment::Center => ((cell - child) / 2.0).max(0.0),
+            GridAlignment::End => (cell - child).max(0.0),
+        }
+    }
+}
+
+impl<T: Data> Grid<T> {
+    /// Create a new, empty `Grid`.
+    ///
+    /// The spacing between tracks defaults to the theme's widget padding.
+    pub fn new() -> Self {
+        Grid {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            column_spacing: theme::WIDGET_PADDING_HORIZONTAL.into(),
+            row_spacing: theme::WIDGET_PADDING_VERTICAL.into(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a column to the right of the existing ones.
+    pub fn with_column(mut self, track: GridTrack) -> Self {
+        self.add_column(track);
+        self
+    }
+
+    /// Builder-style method to add a row below the existing ones.
+    pub fn with_row(mut self, track: GridTrack) -> Self {
+        self.add_row(track);
+        self
+    }
+
+    /// Builder-style method for setting the space between columns.
+    pub fn with_column_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.column_spacing = spacing.into();
+        self
+    }
+
+    /// Builder-style method for setting the space between rows.
+    pub fn with_row_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.row_spacing = spacing.into();
+        self
+    }
+
+    /// Builder-style variant of `add_child`.
+    pub fn with_child(
+        mut self,
+        child: impl Widget<T> + 'static,
+        params: impl Into<GridParams>,
+    ) -> Self {
+        self.add_child(child, params);
+        self
+    }
+
+    /// Add a column to the right of the existing ones.
+    pub fn add_column(&mut self, track: GridTrack) {
+        self.columns.push(track);
+    }
+
+    /// Add a row below the existing ones.
+    pub fn add_row(&mut self, track: GridTrack) {
+        self.rows.push(track);
+    }
+
+    /// Add a child widget in the cell given by `params`.
+    ///
+    /// See also [`with_child`].
+    ///
+    /// [`with_child`]: Grid::with_child
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, params: impl Into<GridParams>) {
+        self.children.push(GridChild {
+            widget: WidgetPod::new(Box::new(child)),
+            params: params.into(),
+            last_layout: None,
+        });
+    }
+}
+
+impl<T: Data> Default for Grid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A child's extent along one axis, for sizing the tracks of that axis.
+struct Extent {
+    start: usize,
+    span: usize,
+    size: f64,
+}
+
+/// Compute the size of each track along one axis.
+fn resolve_tracks(declared: &[GridTrack], extents: &[Extent], spacing: f64, max: f64) -> Vec<f64> {
+    let count = extents
+        .iter()
+        .map(|e| e.start + e.span)
+        .max()
+        .unwrap_or(0)
+        .max(declared.len());
+    let tracks: Vec<GridTrack> = (0..count).map(|i| track_at(declared, i, max)).collect();
+    let mut sizes: Vec<f64> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fixed(size) => size.max(0.0),
+            _ => 0.0,
+        })
+        .collect();
+
+    // Auto tracks first fit the children that are only in them...
+    for extent in extents.iter().filter(|e| e.span == 1) {
+        if tracks[extent.start] == GridTrack::Auto {
+            sizes[extent.start] = sizes[extent.start].max(extent.size);
+        }
+    }
+    // ...then grow evenly for spanning children that still don't fit, unless
+    // a flex track can take up the difference.
+    for extent in extents.iter().filter(|e| e.span > 1) {
+        let span = extent.start..extent.start + extent.span;
+        if tracks[span.clone()]
+            .iter()
+            .any(|t| matches!(t, GridTrack::Flex(_)))
+        {
+            continue;
+        }
+        let autos = tracks[span.clone()]
+            .iter()
+            .filter(|t| **t == GridTrack::Auto)
+            .count();
+        let current = sizes[span.clone()].iter().sum::<f64>() + spacing * (extent.span - 1) as f64;
+        if autos > 0 && extent.size > current {
+            let extra = (extent.size - current) / autos as f64;
+            for i in span.filter(|i| tracks[*i] == GridTrack::Auto) {
+                sizes[i] += extra;
+            }
+        }
+    }
+
+    let total_flex: f64 = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Flex(flex) => flex.max(0.0),
+            _ => 0.0,
+        })
+        .sum();
+    if total_flex > 0.0 {
+        let used = sizes.iter().sum::<f64>() + spacing * count.saturating_sub(1) as f64;
+        let remaining = (max - used).max(0.0);
+        for (size, track) in sizes.iter_mut().zip(&tracks) {
+            if let GridTrack::Flex(flex) = track {
+                *size = remaining * flex.max(0.0) / total_flex;
+            }
+        }
+    }
+    sizes
+}
+
+/// The track at `index`, counting tracks past the declared ones, and flex
+/// tracks in unbounded space, as auto tracks.
+fn track_at(declared: &[GridTrack], index: usize, max: f64) -> GridTrack {
+    match declared.get(index) {
+        Some(GridTrack::Flex(_)) if !max.is_finite() => GridTrack::Auto,
+        Some(track) => *track,
+        None => GridTrack::Auto,
+    }
+}
+
+/// Whether any track of a span is sized to fit its children.
+fn spans_auto(declared: &[GridTrack], start: usize, span: usize, max: f64) -> bool {
+    (start..start + span).any(|i| track_at(declared, i, max) == GridTrack::Auto)
+}
+
+/// The start of each track, and the total length of all of them.
+fn track_offsets(sizes: &[f64], spacing: f64) -> (Vec<f64>, f64) {
+    let mut offsets = Vec::with_capacity(sizes.len());
+    let mut pos = 0.0;
+    for size in sizes {
+        offsets.push(pos);
+        pos += size + spacing;
+    }
+    let total = if sizes.is_empty() { 0.0 } else { pos - spacing };
+    (offsets, total)
+}
+
+/// The length of a span of tracks, including the spacing between them.
+fn span_length(sizes: &[f64], start: usize, span: usize, spacing: f64) -> f64 {
+    sizes[start..start + span].iter().sum::<f64>() + spacing * (span - 1) as f64
+}
+
+impl<T: Data> Widget<T> for Grid<T> {
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+        if ctx.env_key_changed(&self.column_spacing) || ctx.env_key_changed(&self.row_spacing) {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Grid");
+        let column_spacing = self.column_spacing.resolve(env);
+        let row_spacing = self.row_spacing.resolve(env);
+
+        for child in &mut self.children {
+            child.last_layout = None;
+        }
+
+        // Size the columns from the children's natural widths. Only auto
+        // columns depend on them, so other children aren't measured.
+        let max_width = bc.max().width;
+        let measure_bc = BoxConstraints::new(Size::ZERO, Size::new(max_width, f64::INFINITY));
+        let widths: Vec<Extent> = self
+            .children
+            .iter_mut()
+            .map(|child| {
+                let p = child.params;
+                let size = if spans_auto(&self.columns, p.column, p.column_span, max_width) {
+                    child.layout(ctx, &measure_bc, data, env).width
+                } else {
+                    0.0
+                };
+                Extent {
+                    start: p.column,
+                    span: p.column_span,
+                    size,
+                }
+            })
+            .collect();
+        let columns = resolve_tracks(&self.columns, &widths, column_spacing, max_width);
+
+        // Size the rows from the children's heights at their final widths.
+        let max_height = bc.max().height;
+        let heights: Vec<Extent> = self
+            .children
+            .iter_mut()
+            .map(|child| {
+                let p = child.params;
+                if !spans_auto(&self.rows, p.row, p.row_span, max_height) {
+                    return Extent {
+                        start: p.row,
+                        span: p.row_span,
+                        size: 0.0,
+                    };
+                }
+                let width = span_length(&columns, p.column, p.column_span, column_spacing);
+                let min_width = if p.horizontal == GridAlignment::Fill {
+                    width
+                } else {
+                    0.0
+                };
+                let child_bc =
+                    BoxConstraints::new(Size::new(min_width, 0.0), Size::new(width, f64::INFINITY));
+                Extent {
+                    start: p.row,
+                    span: p.row_span,
+                    size: child.layout(ctx, &child_bc, data, env).height,
+                }
+            })
+            .collect();
+        let rows = resolve_tracks(&self.rows, &heights, row_spacing, max_height);
+
+        let (column_offsets, total_width) = track_offsets(&columns, column_spacing);
+        let (row_offsets, total_height) = track_offsets(&rows, row_spacing);
+        let mut paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let p = child.params;
+            let cell = Size::new(
+                span_length(&columns, p.column, p.column_span, column_spacing),
+                span_length(&rows, p.row, p.row_span, row_spacing),
+            );
+            let min = Size::new(
+                if p.horizontal == GridAlignment::Fill {
+                    cell.width
+                } else {
+                    0.0
+                },
+                if p.vertical == GridAlignment::Fill {
+                    cell.height
+                } else {
+                    0.0
+                },
+            );
+            let size = child.layout(ctx, &BoxConstraints::new(min, cell), data, env);
+            let origin = Point::new(
+                column_offsets[p.column] + p.horizontal.offset(size.width, cell.width),
+                row_offsets[p.row] + p.vertical.offset(size.height, cell.height),
+            );
+            child.widget.set_origin(ctx, origin);
+            paint_rect = paint_rect.union(child.widget.paint_rect());
+        }
+
+        let my_size = bc.constrain(Size::new(total_width, total_height));
+        let insets = paint_rect - my_size.to_rect();
+        ctx.set_paint_insets(insets);
+        my_size
+    }
+
+    #[instrument(name = "Grid", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.widget.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::{widget_ids, ModularWidget};
+    use crate::widget::SizedBox;
+    use crate::WidgetExt;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use test_log::test;
+
+    #[test]
+    fn auto_and_flex_columns_with_span() {
+        let [label, field, footer] = widget_ids();
+        let grid = Grid::new()
+            .with_column(GridTrack::Auto)
+            .with_column(GridTrack::Flex(1.0))
+            .with_column_spacing(10.0)
+            .with_row_spacing(5.0)
+            .with_child(
+                SizedBox::empty().fix_size(80.0, 20.0).with_id(label),
+                GridParams::new(0, 0).with_alignment(GridAlignment::Start, GridAlignment::Center),
+            )
+            .with_child(SizedBox::empty().fix_height(30.0).with_id(field), (1, 0))
+            .with_child(
+                SizedBox::empty().fix_height(10.0).with_id(footer),
+                GridParams::new(0, 1).with_span(2, 1),
+            )
+            .align_left();
+
+        Harness::create_simple((), grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(label).layout_rect(),
+                Rect::new(0.0, 5.0, 80.0, 25.0)
+            );
+            assert_eq!(
+                harness.get_state(field).layout_rect(),
+                Rect::new(90.0, 0.0, 400.0, 30.0)
+            );
+            assert_eq!(
+                harness.get_state(footer).layout_rect(),
+                Rect::new(0.0, 35.0, 400.0, 45.0)
+            );
+        });
+    }
+
+    #[test]
+    fn children_are_only_measured_for_auto_tracks() {
+        let counter = |layouts: &Rc<Cell<usize>>, width: f64| {
+            ModularWidget::new(layouts.clone()).layout_fn(move |layouts, _, bc, _, _| {
+                layouts.set(layouts.get() + 1);
+                bc.constrain(Size::new(width, 20.0))
+            })
+        };
+        let [fixed, auto, wide] = [(); 3].map(|_| Rc::new(Cell::new(0)));
+        let grid = Grid::new()
+            .with_column(GridTrack::Fixed(50.0))
+            .with_column(GridTrack::Auto)
+            .with_row(GridTrack::Fixed(30.0))
+            .with_row(GridTrack::Auto)
+            .with_child(counter(&fixed, 40.0), (0, 0))
+            .with_child(counter(&auto, 40.0), (1, 0))
+            // Measuring its height uses the same constraints as measuring its
+            // width, so that layout is reused.
+            .with_child(
+                counter(&wide, f64::INFINITY),
+                GridParams::new(1, 1).with_alignment(GridAlignment::Start, GridAlignment::Fill),
+            )
+            .align_left();
+
+        Harness::create_simple((), grid, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+        });
+        assert_eq!(fixed.get(), 1);
+        assert_eq!(auto.get(), 2);
+        assert_eq!(wide.get(), 2);
+    }
+
+    #[test]
+    fn spanning_child_grows_auto_tracks() {
+        let extents = [
+            Extent {
+                start: 0,
+                span: 1,
+                size: 10.0,
+            },
+            Extent {
+                start: 0,
+                span: 2,
+                size: 50.0,
+            },
+        ];
+        let sizes = resolve_tracks(&[GridTrack::Auto], &extents, 10.0, f64::INFINITY);
+        assert_eq!(sizes, vec![25.0, 15.0]);
+    }
+}
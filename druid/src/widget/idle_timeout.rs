@@ -0,0 +1,146 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that notices when the user stops interacting with a window.
+
+use std::time::Duration;
+
+use tracing::{instrument, trace};
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, TimerToken, Widget};
+
+/// A [`Controller`] that calls a closure once no input has arrived for a while.
+///
+/// This is meant for kiosk, exhibit and point-of-sale apps that should go back
+/// to an attract screen, or lock themselves, when left alone. Wrap the root
+/// widget of a window in it to watch all of that window's pointer and keyboard
+/// input.
+///
+/// The closure is called once per idle period; the next input starts the
+/// countdown again. The countdown also starts when the widget is added to the tree.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::widget::{IdleTimeout, Label, WidgetExt};
+///
+/// let idle = IdleTimeout::new(Duration::from_secs(90), |_ctx, screen: &mut u32, _env| {
+///     *screen = 0;
+/// });
+/// let root = Label::new(|screen: &u32, _env: &_| format!("Screen {}", screen)).controller(idle);
+/// ```
+pub struct IdleTimeout<T> {
+    timeout: Duration,
+    action: Box<dyn Fn(&mut EventCtx, &mut T, &Env)>,
+    timer: TimerToken,
+}
+
+impl<T: Data> IdleTimeout<T> {
+    /// Create a new `IdleTimeout` that calls `action` after `timeout` without input.
+    pub fn new(timeout: Duration, action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        IdleTimeout {
+            timeout,
+            action: Box::new(action),
+            timer: TimerToken::INVALID,
+        }
+    }
+}
+
+/// Whether `event` is the user interacting with the window.
+fn is_input(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMove(_)
+            | Event::Wheel(_)
+            | Event::Zoom(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+    )
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for IdleTimeout<T> {
+    #[instrument(
+        name = "IdleTimeout",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                trace!("No input for {:?}", self.timeout);
+                self.timer = TimerToken::INVALID;
+                (self.action)(ctx, data, env);
+            }
+            _ => {
+                if is_input(event) {
+                    self.timer = ctx.request_timer(self.timeout);
+                }
+                child.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "IdleTimeout",
+        level = "trace",
+        skip(self, child, ctx, event, data, env)
+    )]
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            self.timer = ctx.request_timer(self.timeout);
+        }
+
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{SizedBox, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn fires_once_after_inactivity() {
+        let idle = IdleTimeout::new(Duration::from_secs(60), |_, count: &mut u32, _| {
+            *count += 1;
+        });
+        let widget = SizedBox::empty().expand().controller(idle);
+
+        Harness::create_simple(0, widget, |harness| {
+            harness.send_initial_events();
+            harness.advance(Duration::from_secs(59));
+            harness.event(Event::MouseMove(move_mouse((10., 10.))));
+            harness.advance(Duration::from_secs(59));
+            assert_eq!(*harness.data(), 0);
+            harness.advance(Duration::from_secs(2));
+            assert_eq!(*harness.data(), 1);
+            harness.advance(Duration::from_secs(120));
+            assert_eq!(*harness.data(), 1);
+        });
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A rotary knob widget.
+
+use std::f64::consts::PI;
+
+use tracing::{instrument, trace, warn};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{Arc, Circle, Line, Vec2};
+use crate::widget::prelude::*;
+use crate::{theme, Point};
+
+/// The angle of the minimum value, measured clockwise from the positive x axis.
+const START_ANGLE: f64 = 0.75 * PI;
+/// The angle covered between the minimum and the maximum value.
+const SWEEP_ANGLE: f64 = 1.5 * PI;
+/// How much slower the value changes while shift is held.
+const FINE_ADJUST_FACTOR: f64 = 0.1;
+/// The width of the arc showing the value.
+const ARC_WIDTH: f64 = 3.0;
+
+/// A rotary knob for picking a `f64` from a range.
+///
+/// The value is changed by dragging around the center of the knob: a full
+/// sweep from the lowest to the highest position covers the whole range. While
+/// shift is held, the value changes ten times slower, for fine adjustments.
+pub struct Knob {
+    min: f64,
+    max: f64,
+    step: Option<f64>,
+    /// The angle of the mouse relative to the center during a drag.
+    drag_angle: Option<f64>,
+    /// The value during a drag, before it is snapped to a step.
+    drag_value: f64,
+}
+
+impl Knob {
+    /// Create a new `Knob`, covering the range `0.0..=1.0`.
+    pub fn new() -> Self {
+        Knob {
+            min: 0.0,
+            max: 1.0,
+            step: None,
+            drag_angle: None,
+            drag_value: 0.0,
+        }
+    }
+
+    /// Builder-style method to set the range covered by this knob.
+    ///
+    /// The default range is `0.0..=1.0`.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        if max < min {
+            warn!(
+                "min({}) should be less than max({}), swapping the values",
+                min, max
+            );
+            self.min = max;
+            self.max = min;
+        } else {
+            self.min = min;
+            self.max = max;
+        }
+        self
+    }
+
+    /// Builder-style method to set the stepping.
+    ///
+    /// The value is snapped to the nearest multiple of `step`, counted from the
+    /// minimum. By default, the value is not snapped.
+    pub fn with_step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = Some(step);
+        } else {
+            warn!("bad stepping ({}): must be positive", step);
+        }
+        self
+    }
+
+    fn snap(&self, value: f64) -> f64 {
+        let value = value.clamp(self.min, self.max);
+        match self.step {
+            Some(step) => (((value - self.min) / step).round() * step + self.min).min(self.max),
+            None => value,
+        }
+    }
+
+    /// The position of `value` along the sweep, from `0.0` to `1.0`.
+    fn normalize(&self, value: f64) -> f64 {
+        if self.max > self.min {
+            (value.clamp(self.min, self.max) - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The smallest rotation from `from` to `to`, in radians.
+fn angle_delta(from: f64, to: f64) -> f64 {
+    let delta = (to - from) % (2.0 * PI);
+    if delta > PI {
+        delta - 2.0 * PI
+    } else if delta <= -PI {
+        delta + 2.0 * PI
+    } else {
+        delta
+    }
+}
+
+impl Default for Knob {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget<f64> for Knob {
+    #[instrument(name = "Knob", level = "trace", skip(self, ctx, event, data, _env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, _env: &Env) {
+        let center = ctx.size().to_rect().center();
+        let angle_of = |pos: Point| {
+            let offset: Vec2 = pos - center;
+            offset.y.atan2(offset.x)
+        };
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() && !ctx.is_disabled() => {
+                ctx.set_active(true);
+                self.drag_angle = Some(angle_of(mouse.pos));
+                self.drag_value = *data;
+                ctx.request_paint();
+            }
+            Event::MouseMove(mouse) if ctx.is_active() => {
+                if let Some(last_angle) = self.drag_angle {
+                    let angle = angle_of(mouse.pos);
+                    let mut delta = angle_delta(last_angle, angle) / SWEEP_ANGLE;
+                    if mouse.mods.shift() {
+                        delta *= FINE_ADJUST_FACTOR;
+                    }
+                    self.drag_angle = Some(angle);
+                    self.drag_value =
+                        (self.drag_value + delta * (self.max - self.min)).clamp(self.min, self.max);
+                    *data = self.snap(self.drag_value);
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                self.drag_angle = None;
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(name = "Knob", level = "trace", skip(self, ctx, event, _data, _env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &f64, _env: &Env) {
+        if let LifeCycle::HotChanged(_) | LifeCycle::DisabledChanged(_) = event {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Knob", level = "trace", skip(self, ctx, old_data, data, _env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &f64, data: &f64, _env: &Env) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(name = "Knob", level = "trace", skip(self, _ctx, bc, _data, env))]
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &f64,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("Knob");
+        let diameter = env.get(theme::BORDERED_WIDGET_HEIGHT) * 2.0;
+        let size = bc.constrain(Size::new(diameter, diameter));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Knob", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &f64, env: &Env) {
+        let size = ctx.size();
+        let center = size.to_rect().center();
+        let radius = size.width.min(size.height) / 2.0 - ARC_WIDTH;
+        let value_angle = START_ANGLE + SWEEP_ANGLE * self.normalize(*data);
+
+        let track = Arc {
+            center,
+            radii: Vec2::new(radius, radius),
+            start_angle: START_ANGLE,
+            sweep_angle: SWEEP_ANGLE,
+            x_rotation: 0.0,
+        };
+        ctx.stroke(track, &env.get(theme::BACKGROUND_LIGHT), ARC_WIDTH);
+
+        let value_color = if ctx.is_disabled() {
+            env.get(theme::DISABLED_FOREGROUND_DARK)
+        } else {
+            env.get(theme::PRIMARY_LIGHT)
+        };
+        let value_arc = Arc {
+            sweep_angle: value_angle - START_ANGLE,
+            ..track
+        };
+        ctx.stroke(value_arc, &value_color, ARC_WIDTH);
+
+        let body = Circle::new(center, radius - ARC_WIDTH * 1.5);
+        let border_color = if ctx.is_hot() || ctx.is_active() {
+            env.get(theme::FOREGROUND_LIGHT)
+        } else {
+            env.get(theme::BORDER_LIGHT)
+        };
+        ctx.fill(body, &env.get(theme::BUTTON_LIGHT));
+        ctx.stroke(body, &border_color, 1.0);
+
+        let direction = Vec2::from_angle(value_angle);
+        let pointer = Line::new(
+            center + direction * body.radius * 0.3,
+            center + direction * body.radius * 0.85,
+        );
+        ctx.stroke(pointer, &env.get(theme::TEXT_COLOR), 2.0);
+    }
+
+    fn debug_state(&self, data: &f64) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{Modifiers, MouseButton, MouseButtons, MouseEvent};
+    use test_log::test;
+
+    #[test]
+    fn angle_delta_takes_the_short_way() {
+        assert!((angle_delta(0.1, 0.3) - 0.2).abs() < 1e-9);
+        assert!((angle_delta(PI - 0.1, -PI + 0.1) - 0.2).abs() < 1e-9);
+        assert!((angle_delta(-PI + 0.1, PI - 0.1) + 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drag_and_fine_adjust() {
+        let knob = Knob::new().with_range(0.0, 3.0);
+        let widget = SizedBox::new(knob).width(100.).height(100.);
+
+        Harness::create_simple(1.0, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let left = MouseEvent {
+                button: MouseButton::Left,
+                buttons: MouseButtons::new().with(MouseButton::Left),
+                ..move_mouse((50., 10.))
+            };
+
+            // A quarter turn clockwise is a third of the sweep.
+            harness.event(Event::MouseMove(left.clone()));
+            harness.event(Event::MouseDown(left.clone()));
+            harness.event(Event::MouseMove(MouseEvent {
+                pos: Point::new(90., 50.),
+                ..left.clone()
+            }));
+            assert!((*harness.data() - 2.0).abs() < 1e-9);
+
+            // With shift, turning back only changes the value by a tenth as much.
+            harness.event(Event::MouseMove(MouseEvent {
+                pos: Point::new(50., 10.),
+                mods: Modifiers::SHIFT,
+                ..left.clone()
+            }));
+            assert!((*harness.data() - 1.9).abs() < 1e-9);
+            harness.event(Event::MouseUp(left));
+        });
+    }
+}
@@ -102,6 +102,41 @@ pub trait ListIter<T>: Data {
 
     /// Return data length.
     fn data_len(&self) -> usize;
+
+    /// Call `cb` with the data child at `index`, if there is one.
+    ///
+    /// The default implementation goes through [`for_each`]; collections with
+    /// random access override it to skip the other children.
+    ///
+    /// [`for_each`]: ListIter::for_each
+    fn for_index(&self, index: usize, cb: impl FnOnce(&T)) {
+        let mut cb = Some(cb);
+        self.for_each(|item, i| {
+            if i == index {
+                if let Some(cb) = cb.take() {
+                    cb(item);
+                }
+            }
+        });
+    }
+
+    /// Call `cb` with the data child at `index`, if there is one, and keep
+    /// track of changes to it.
+    ///
+    /// The default implementation goes through [`for_each_mut`]; collections
+    /// with random access override it to only clone the child at `index`.
+    ///
+    /// [`for_each_mut`]: ListIter::for_each_mut
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut T)) {
+        let mut cb = Some(cb);
+        self.for_each_mut(|item, i| {
+            if i == index {
+                if let Some(cb) = cb.take() {
+                    cb(item);
+                }
+            }
+        });
+    }
 }
 #[cfg(feature = "im")]
 impl<T: Data> ListIter<T> for Vector<T> {
@@ -124,6 +159,22 @@ impl<T: Data> ListIter<T> for Vector<T> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn for_index(&self, index: usize, cb: impl FnOnce(&T)) {
+        if let Some(item) = self.get(index) {
+            cb(item);
+        }
+    }
+
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut T)) {
+        if let Some(item) = self.get(index) {
+            let mut new_element = item.to_owned();
+            cb(&mut new_element);
+            if !new_element.same(item) {
+                self[index] = new_element;
+            }
+        }
+    }
 }
 
 //An implementation for ListIter<(K, V)> has been omitted due to problems
@@ -184,6 +235,26 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Vector<T>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn for_index(&self, index: usize, cb: impl FnOnce(&(S, T))) {
+        if let Some(item) = self.1.get(index) {
+            cb(&(self.0.to_owned(), item.to_owned()));
+        }
+    }
+
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut (S, T))) {
+        if let Some(element) = self.1.get(index) {
+            let mut d = (self.0.clone(), element.to_owned());
+            cb(&mut d);
+
+            if !self.0.same(&d.0) {
+                self.0 = d.0;
+            }
+            if !element.same(&d.1) {
+                self.1[index] = d.1;
+            }
+        }
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<Vec<T>> {
@@ -221,6 +292,23 @@ impl<T: Data> ListIter<T> for Arc<Vec<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn for_index(&self, index: usize, cb: impl FnOnce(&T)) {
+        if let Some(item) = self.get(index) {
+            cb(item);
+        }
+    }
+
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut T)) {
+        if let Some(item) = self.get(index) {
+            let mut d = item.to_owned();
+            cb(&mut d);
+
+            if !item.same(&d) {
+                Arc::make_mut(self)[index] = d;
+            }
+        }
+    }
 }
 
 // S == shared data type
@@ -262,6 +350,26 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<Vec<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn for_index(&self, index: usize, cb: impl FnOnce(&(S, T))) {
+        if let Some(item) = self.1.get(index) {
+            cb(&(self.0.clone(), item.to_owned()));
+        }
+    }
+
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut (S, T))) {
+        if let Some(item) = self.1.get(index) {
+            let mut d = (self.0.clone(), item.to_owned());
+            cb(&mut d);
+
+            if !self.0.same(&d.0) {
+                self.0 = d.0;
+            }
+            if !item.same(&d.1) {
+                Arc::make_mut(&mut self.1)[index] = d.1;
+            }
+        }
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
@@ -299,6 +407,23 @@ impl<T: Data> ListIter<T> for Arc<VecDeque<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn for_index(&self, index: usize, cb: impl FnOnce(&T)) {
+        if let Some(item) = self.get(index) {
+            cb(item);
+        }
+    }
+
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut T)) {
+        if let Some(item) = self.get(index) {
+            let mut d = item.to_owned();
+            cb(&mut d);
+
+            if !item.same(&d) {
+                Arc::make_mut(self)[index] = d;
+            }
+        }
+    }
 }
 
 // S == shared data type
@@ -340,6 +465,26 @@ impl<S: Data, T: Data> ListIter<(S, T)> for (S, Arc<VecDeque<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn for_index(&self, index: usize, cb: impl FnOnce(&(S, T))) {
+        if let Some(item) = self.1.get(index) {
+            cb(&(self.0.clone(), item.to_owned()));
+        }
+    }
+
+    fn for_index_mut(&mut self, index: usize, cb: impl FnOnce(&mut (S, T))) {
+        if let Some(item) = self.1.get(index) {
+            let mut d = (self.0.clone(), item.to_owned());
+            cb(&mut d);
+
+            if !self.0.same(&d.0) {
+                self.0 = d.0;
+            }
+            if !item.same(&d.1) {
+                Arc::make_mut(&mut self.1)[index] = d.1;
+            }
+        }
+    }
 }
 
 impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
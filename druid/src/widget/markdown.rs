@@ -0,0 +1,313 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that renders markdown.
+
+use pulldown_cmark::{Event as ParseEvent, Options, Parser, Tag};
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::text::{AttributesAdder, RichText, RichTextBuilder};
+use crate::widget::prelude::*;
+use crate::widget::{LineBreaking, RawLabel};
+use crate::{theme, FontFamily, FontStyle, FontWeight, Selector};
+
+/// A widget that displays a markdown string as styled text.
+///
+/// Headings, emphasis, strikethrough, lists, block quotes and code are shown
+/// with matching styles. Clicking a link submits [`Markdown::OPEN_LINK`] with
+/// the link's target; handle it in an [`AppDelegate`] to open the link.
+///
+/// The text is parsed and laid out again only when the data changes to a
+/// different string. To use the styled text elsewhere, for instance in a
+/// [`RawLabel`] with different settings, call [`Markdown::render`]; markdown
+/// that was already parsed can be rendered with [`Markdown::render_events`].
+///
+/// This widget is only available with the `markdown` feature.
+///
+/// [`AppDelegate`]: crate::AppDelegate
+pub struct Markdown {
+    label: RawLabel<RichText>,
+    rendered: RichText,
+    /// The markdown that `rendered` was built from.
+    source: String,
+}
+
+impl Markdown {
+    /// The command submitted when a link is clicked, with the target of the link.
+    pub const OPEN_LINK: Selector<String> = Selector::new("druid-builtin.markdown.open-link");
+
+    /// Create a new `Markdown` widget.
+    pub fn new() -> Self {
+        Markdown {
+            label: RawLabel::new().with_line_break_mode(LineBreaking::WordWrap),
+            rendered: RichText::new("".into()),
+            source: String::new(),
+        }
+    }
+
+    /// Builder-style method for setting the [`LineBreaking`] behaviour.
+    ///
+    /// The default is [`LineBreaking::WordWrap`].
+    pub fn with_line_break_mode(mut self, mode: LineBreaking) -> Self {
+        self.label.set_line_break_mode(mode);
+        self
+    }
+
+    /// Parse a markdown string into styled text.
+    pub fn render(text: &str) -> RichText {
+        Markdown::render_events(Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH))
+    }
+
+    /// Build styled text from markdown that was already parsed.
+    ///
+    /// This is useful when the events come from a parser with other
+    /// [`Options`], or were filtered or rewritten first.
+    pub fn render_events<'a>(events: impl IntoIterator<Item = ParseEvent<'a>>) -> RichText {
+        let mut current_pos = 0;
+        let mut builder = RichTextBuilder::new();
+        let mut tag_stack = Vec::new();
+        // The next number of each enclosing list, or `None` for bulleted lists.
+        let mut lists: Vec<Option<u64>> = Vec::new();
+        let mut nested_list_ended = false;
+
+        for event in events {
+            match event {
+                ParseEvent::Start(tag) => {
+                    match &tag {
+                        Tag::List(first) => {
+                            // A nested list starts on the line after its parent item.
+                            if !lists.is_empty() {
+                                builder.push("\n");
+                                current_pos += 1;
+                            }
+                            lists.push(*first);
+                        }
+                        Tag::Item => {
+                            let indent = "    ".repeat(lists.len().saturating_sub(1));
+                            let marker = match lists.last_mut() {
+                                Some(Some(number)) => {
+                                    *number += 1;
+                                    format!("{indent}{}. ", *number - 1)
+                                }
+                                _ => format!("{indent}\u{2022} "),
+                            };
+                            builder.push(&marker);
+                            current_pos += marker.len();
+                        }
+                        _ => (),
+                    }
+                    tag_stack.push((current_pos, tag));
+                    nested_list_ended = false;
+                }
+                ParseEvent::Text(txt) => {
+                    builder.push(&txt);
+                    current_pos += txt.len();
+                }
+                ParseEvent::End(end_tag) => {
+                    let (start_off, tag) = tag_stack
+                        .pop()
+                        .expect("parser does not return unbalanced tags");
+                    assert_eq!(end_tag, tag, "mismatched tags?");
+                    add_attribute_for_tag(
+                        &tag,
+                        builder.add_attributes_for_range(start_off..current_pos),
+                    );
+                    if let Tag::List(_) = tag {
+                        lists.pop();
+                    }
+                    let separator = match tag {
+                        // The nested list already ended the line.
+                        Tag::Item if nested_list_ended => "",
+                        _ => separator_after_tag(&tag, lists.is_empty()),
+                    };
+                    nested_list_ended = matches!(tag, Tag::List(_)) && !lists.is_empty();
+                    builder.push(separator);
+                    current_pos += separator.len();
+                }
+                ParseEvent::Code(txt) => {
+                    builder.push(&txt).font_family(FontFamily::MONOSPACE);
+                    current_pos += txt.len();
+                }
+                ParseEvent::Html(txt) => {
+                    builder
+                        .push(&txt)
+                        .font_family(FontFamily::MONOSPACE)
+                        .text_color(theme::DISABLED_TEXT_COLOR);
+                    current_pos += txt.len();
+                }
+                ParseEvent::SoftBreak => {
+                    builder.push(" ");
+                    current_pos += 1;
+                }
+                ParseEvent::HardBreak | ParseEvent::Rule => {
+                    builder.push("\n");
+                    current_pos += 1;
+                }
+                _ => (),
+            }
+        }
+        builder.build()
+    }
+}
+
+/// The text that separates the end of an element from what follows it.
+fn separator_after_tag(tag: &Tag, outside_lists: bool) -> &'static str {
+    match tag {
+        Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link(..) => "",
+        Tag::Item => "\n",
+        // Nested lists end with the item that contains them.
+        Tag::List(_) if !outside_lists => "",
+        Tag::List(_) => "\n",
+        _ => "\n\n",
+    }
+}
+
+fn add_attribute_for_tag(tag: &Tag, mut attrs: AttributesAdder) {
+    match tag {
+        Tag::Heading(level) => {
+            let font_size = match level {
+                1 => 32.0,
+                2 => 26.0,
+                3 => 21.0,
+                4 => 18.0,
+                5 => 16.0,
+                _ => 15.0,
+            };
+            attrs.size(font_size).weight(FontWeight::BOLD);
+        }
+        Tag::BlockQuote => {
+            attrs
+                .style(FontStyle::Italic)
+                .text_color(theme::DISABLED_TEXT_COLOR);
+        }
+        Tag::CodeBlock(_) => {
+            attrs.font_family(FontFamily::MONOSPACE);
+        }
+        Tag::Emphasis => {
+            attrs.style(FontStyle::Italic);
+        }
+        Tag::Strong => {
+            attrs.weight(FontWeight::BOLD);
+        }
+        Tag::Strikethrough => {
+            attrs.strikethrough(true);
+        }
+        Tag::Link(_link_ty, target, _title) => {
+            attrs
+                .underline(true)
+                .text_color(theme::PRIMARY_LIGHT)
+                .link(Markdown::OPEN_LINK.with(target.to_string()));
+        }
+        _ => (),
+    }
+}
+
+impl Default for Markdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data + AsRef<str>> Widget<T> for Markdown {
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, event, _data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        self.label.event(ctx, event, &mut self.rendered, env);
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.source = data.as_ref().to_string();
+            self.rendered = Markdown::render(&self.source);
+        }
+        self.label.lifecycle(ctx, event, &self.rendered, env);
+    }
+
+    #[instrument(
+        name = "Markdown",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let old_rendered = self.rendered.clone();
+        if !old_data.same(data) && data.as_ref() != self.source {
+            trace!("Markdown source changed");
+            self.source = data.as_ref().to_string();
+            self.rendered = Markdown::render(&self.source);
+        }
+        self.label.update(ctx, &old_rendered, &self.rendered, env);
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, bc, _data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        self.label.layout(ctx, bc, &self.rendered, env)
+    }
+
+    #[instrument(name = "Markdown", level = "trace", skip(self, ctx, _data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        self.label.paint(ctx, &self.rendered, env)
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Markdown".to_string(),
+            main_value: data.as_ref().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piet::TextStorage as _;
+    use test_log::test;
+
+    #[test]
+    fn lists_get_markers() {
+        let rendered = Markdown::render("# Title\n\n- one\n- two\n\n1. first\n2. second\n");
+        assert_eq!(
+            rendered.as_str(),
+            "Title\n\n\u{2022} one\n\u{2022} two\n\n1. first\n2. second\n\n"
+        );
+    }
+
+    #[test]
+    fn nested_lists_are_indented() {
+        let rendered = Markdown::render("- outer\n  - inner\n- last\n");
+        assert_eq!(
+            rendered.as_str(),
+            "\u{2022} outer\n    \u{2022} inner\n\u{2022} last\n\n"
+        );
+    }
+
+    #[test]
+    fn inline_styles_add_no_text() {
+        let rendered = Markdown::render("*a* **b** [c](https://example.com) `d`");
+        assert_eq!(rendered.as_str(), "a b c d\n\n");
+    }
+
+    #[test]
+    fn pre_parsed_events() {
+        let events = Parser::new("# Title\n\n*text*").filter(|event| {
+            !matches!(
+                event,
+                ParseEvent::Start(Tag::Emphasis) | ParseEvent::End(Tag::Emphasis)
+            )
+        });
+        let rendered = Markdown::render_events(events);
+        assert_eq!(rendered.as_str(), "Title\n\ntext\n\n");
+    }
+}
@@ -21,34 +21,52 @@ mod widget_wrapper;
 mod added;
 mod align;
 mod aspect_ratio_box;
+mod breadcrumb;
 mod button;
 mod checkbox;
+#[cfg(feature = "im")]
+#[cfg_attr(docsrs, doc(cfg(feature = "im")))]
+mod chips;
 mod click;
 mod clip_box;
+mod combo_box;
 mod common;
 mod container;
+mod context_click;
 mod controller;
 mod disable_if;
+mod dock;
 mod either;
 mod env_scope;
+mod fixed_tick;
 mod flex;
+mod focus_scope;
+mod grid;
 mod identity_wrapper;
+mod idle_timeout;
 mod image;
 mod intrinsic_width;
 mod invalidation;
+mod knob;
 mod label;
 mod lens_wrap;
 mod list;
+#[cfg(feature = "markdown")]
+#[cfg_attr(docsrs, doc(cfg(feature = "markdown")))]
+mod markdown;
 mod maybe;
 mod padding;
 mod painter;
 mod parse;
+mod popup;
+mod presence;
 mod progress_bar;
 mod radio;
 mod scope;
 mod scroll;
 mod sized_box;
 mod slider;
+mod spin_box;
 mod spinner;
 mod split;
 mod stepper;
@@ -56,59 +74,84 @@ mod stepper;
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 mod svg;
 mod switch;
+mod table;
 mod tabs;
 mod textbox;
+mod toolbar;
+mod tree;
 mod value_textbox;
 mod view_switcher;
+mod virtual_list;
 #[allow(clippy::module_inception)]
 mod widget;
 mod widget_ext;
+mod wrap;
 mod z_stack;
 
 pub use self::image::Image;
 pub use added::Added;
 pub use align::Align;
 pub use aspect_ratio_box::AspectRatioBox;
+pub use breadcrumb::Breadcrumb;
 pub use button::Button;
 pub use checkbox::Checkbox;
+#[cfg(feature = "im")]
+pub use chips::Chips;
 pub use click::Click;
 pub use clip_box::{ClipBox, Viewport};
+pub use combo_box::ComboBox;
 pub use common::FillStrat;
 pub use container::Container;
+pub use context_click::ContextClick;
 pub use controller::{Controller, ControllerHost};
 pub use disable_if::DisabledIf;
+pub use dock::{DockLayout, DockManager, DockSide, ParseDockLayoutError};
 pub use either::Either;
 pub use env_scope::EnvScope;
+pub use fixed_tick::FixedTick;
 pub use flex::{Axis, CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use focus_scope::FocusScope;
+pub use grid::{Grid, GridAlignment, GridParams, GridTrack};
 pub use identity_wrapper::IdentityWrapper;
+pub use idle_timeout::IdleTimeout;
 pub use intrinsic_width::IntrinsicWidth;
+pub use knob::Knob;
 pub use label::{Label, LabelText, LineBreaking, RawLabel};
 pub use lens_wrap::LensWrap;
 pub use list::{List, ListIter};
+#[cfg(feature = "markdown")]
+pub use markdown::Markdown;
 pub use maybe::Maybe;
 pub use padding::Padding;
 pub use painter::{BackgroundBrush, Painter};
 #[allow(deprecated)]
 pub use parse::Parse;
+pub use presence::{Presence, RemoteCursor};
 pub use progress_bar::ProgressBar;
 pub use radio::{Radio, RadioGroup};
 pub use scope::{DefaultScopePolicy, LensScopeTransfer, Scope, ScopePolicy, ScopeTransfer};
 pub use scroll::Scroll;
 pub use sized_box::SizedBox;
 pub use slider::{KnobStyle, RangeSlider, Slider};
+pub use spin_box::SpinBox;
 pub use spinner::Spinner;
 pub use split::Split;
 pub use stepper::Stepper;
 #[cfg(feature = "svg")]
 pub use svg::{Svg, SvgData};
 pub use switch::Switch;
+pub use table::{SortDirection, Table, TableColumn};
 pub use tabs::{AddTab, TabInfo, Tabs, TabsEdge, TabsPolicy, TabsState, TabsTransition};
 pub use textbox::TextBox;
+pub use toolbar::Toolbar;
+pub use tree::{Tree, TreeNode};
 pub use value_textbox::{TextBoxEvent, ValidationDelegate, ValueTextBox};
 pub use view_switcher::ViewSwitcher;
+pub use virtual_list::VirtualList;
 pub use widget::{Widget, WidgetId};
 pub use widget_ext::WidgetExt;
 pub use widget_wrapper::WidgetWrapper;
+pub use wrap::Wrap;
 pub use z_stack::ZStack;
 
 /// The types required to implement a [`Widget`].
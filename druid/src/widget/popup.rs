@@ -0,0 +1,91 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for widgets that show a menu of rows in a dropdown window.
+//!
+//! The widget that opens the popup must be the child of a [`WidgetPod`] with
+//! the same data, as [`EventCtx::new_sub_window`] requires, so the public
+//! widgets keep their contents in a pod of their own. Without it, a popup of a
+//! widget wrapped in a lens would sync its data with the wrong pod.
+//!
+//! [`WidgetPod`]: crate::WidgetPod
+
+use crate::widget::prelude::*;
+use crate::{theme, KbKey, Point, Rect, WindowConfig, WindowLevel};
+
+/// The height of each row of a popup.
+pub(crate) const ROW_HEIGHT: f64 = 24.0;
+
+/// The config for a popup of `size` whose top left corner is at `origin`, in
+/// the coordinate space of the widget opening it.
+pub(crate) fn config(ctx: &EventCtx, origin: Point, size: Size) -> WindowConfig {
+    WindowConfig::default()
+        .show_titlebar(false)
+        .resizable(false)
+        .window_size(size)
+        .set_level(WindowLevel::DropDown(ctx.window().clone()))
+        .set_position(ctx.to_window(origin))
+}
+
+/// The row at height `y` in a popup with `rows` rows starting at height zero.
+pub(crate) fn row_at(y: f64, rows: usize) -> Option<usize> {
+    let row = (y / ROW_HEIGHT).floor();
+    (row >= 0.0 && (row as usize) < rows).then_some(row as usize)
+}
+
+/// Handle the events that all popups handle the same way: they take focus
+/// when they are shown, and close on escape.
+pub(crate) fn event(ctx: &mut EventCtx, event: &Event) {
+    match event {
+        Event::WindowConnected => ctx.request_focus(),
+        Event::KeyDown(key) if key.key == KbKey::Escape => {
+            ctx.window().close();
+            ctx.set_handled();
+        }
+        _ => (),
+    }
+}
+
+/// Register the popup for focus, so that it gets keyboard events.
+pub(crate) fn lifecycle(ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+    if let LifeCycle::BuildFocusChain = event {
+        ctx.register_for_focus();
+    }
+}
+
+/// Close the popup when its window stops being active, which is what happens
+/// when the user clicks anywhere outside of it.
+pub(crate) fn update(ctx: &mut UpdateCtx, env: &Env) {
+    if ctx.env_key_changed(&theme::WINDOW_IS_ACTIVE) && !env.get(theme::WINDOW_IS_ACTIVE) {
+        ctx.window().close();
+    }
+}
+
+/// Fill the background of the popup.
+pub(crate) fn paint_background(ctx: &mut PaintCtx, env: &Env) {
+    let rect = ctx.size().to_rect();
+    ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+}
+
+/// Highlight the row whose top edge is at `y`.
+pub(crate) fn paint_highlight(ctx: &mut PaintCtx, y: f64, env: &Env) {
+    let highlight = Rect::new(0.0, y, ctx.size().width, y + ROW_HEIGHT);
+    ctx.fill(highlight, &env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR));
+}
+
+/// Stroke the border of the popup, over its contents.
+pub(crate) fn paint_border(ctx: &mut PaintCtx, env: &Env) {
+    let rect = ctx.size().to_rect().inset(-0.5);
+    ctx.stroke(rect, &env.get(theme::BORDER_DARK), 1.0);
+}
@@ -0,0 +1,247 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows the cursors of remote collaborators.
+
+use std::collections::BTreeMap;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::Line;
+use crate::widget::prelude::*;
+use crate::{theme, ArcStr, Color, Point, Rect, Selector, TextLayout, WidgetPod};
+
+/// The width of the line drawn for a cursor.
+const CURSOR_WIDTH: f64 = 2.0;
+/// The height of the line drawn for a cursor.
+const CURSOR_HEIGHT: f64 = 18.0;
+/// The padding around the text of a cursor's label.
+const LABEL_PADDING: f64 = 2.0;
+
+/// The cursor and selection of a remote collaborator.
+#[derive(Debug, Clone)]
+pub struct RemoteCursor {
+    /// Identifies the collaborator. Updates with the same id replace each other.
+    pub id: u64,
+    /// The position of the cursor, in the coordinate space of the [`Presence`].
+    pub position: Point,
+    /// The rectangles covered by the collaborator's selection, if any.
+    pub selection: Vec<Rect>,
+    /// The name shown next to the cursor.
+    pub label: ArcStr,
+    /// The color of the cursor, its label, and its selection.
+    pub color: Color,
+}
+
+struct CursorLayer {
+    cursor: RemoteCursor,
+    label: TextLayout<ArcStr>,
+}
+
+/// A widget that paints the cursors and selections of remote collaborators
+/// over its child.
+///
+/// The cursors are fed to it with commands, typically submitted from the
+/// thread that talks to the sync service through an [`ExtEventSink`]:
+/// [`RemoteCursor::UPDATE`] adds or moves a cursor, and
+/// [`RemoteCursor::REMOVE`] removes one. Only the areas of the cursors that
+/// changed are repainted, so frequent updates don't repaint the child.
+///
+/// [`ExtEventSink`]: crate::ExtEventSink
+pub struct Presence<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    cursors: BTreeMap<u64, CursorLayer>,
+}
+
+impl RemoteCursor {
+    /// Add or move a remote cursor in a [`Presence`].
+    pub const UPDATE: Selector<RemoteCursor> =
+        Selector::new("druid-builtin.presence.update-cursor");
+
+    /// Remove the remote cursor with the given id from a [`Presence`].
+    pub const REMOVE: Selector<u64> = Selector::new("druid-builtin.presence.remove-cursor");
+}
+
+impl CursorLayer {
+    /// The area covered by the label, above the cursor.
+    fn label_rect(&self) -> Rect {
+        let label_size = self.label.size();
+        let size = Size::new(
+            label_size.width + LABEL_PADDING * 2.0,
+            label_size.height + LABEL_PADDING * 2.0,
+        );
+        let pos = self.cursor.position;
+        Rect::from_origin_size((pos.x, pos.y - size.height), size)
+    }
+
+    /// The area covered by the cursor, its label and its selection.
+    fn bounds(&self) -> Rect {
+        let pos = self.cursor.position;
+        let cursor = Rect::new(pos.x, pos.y, pos.x + CURSOR_WIDTH, pos.y + CURSOR_HEIGHT);
+        self.cursor
+            .selection
+            .iter()
+            .fold(cursor.union(self.label_rect()), |bounds, rect| {
+                bounds.union(*rect)
+            })
+            .inflate(1.0, 1.0)
+    }
+}
+
+impl<T: Data> Presence<T> {
+    /// Create a new `Presence` widget around `child`.
+    pub fn new(child: impl Widget<T> + 'static) -> Self {
+        Presence {
+            child: WidgetPod::new(child).boxed(),
+            cursors: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Presence<T> {
+    #[instrument(name = "Presence", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(cursor) = cmd.get(RemoteCursor::UPDATE) {
+                let mut layer = match self.cursors.remove(&cursor.id) {
+                    Some(old) => {
+                        ctx.request_paint_rect(old.bounds());
+                        old
+                    }
+                    None => CursorLayer {
+                        cursor: cursor.clone(),
+                        label: TextLayout::new(),
+                    },
+                };
+                layer.cursor = cursor.clone();
+                layer.label.set_text(cursor.label.clone());
+                layer
+                    .label
+                    .set_text_size(env.get(theme::TEXT_SIZE_NORMAL) * 0.8);
+                layer.label.set_text_color(Color::WHITE);
+                layer.label.rebuild_if_needed(ctx.text(), env);
+                ctx.request_paint_rect(layer.bounds());
+                self.cursors.insert(cursor.id, layer);
+                ctx.set_handled();
+                return;
+            }
+            if let Some(id) = cmd.get(RemoteCursor::REMOVE) {
+                if let Some(old) = self.cursors.remove(id) {
+                    trace!("Removed cursor {}", id);
+                    ctx.request_paint_rect(old.bounds());
+                }
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Presence", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Presence",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Presence", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_origin(ctx, Point::ORIGIN);
+        ctx.set_paint_insets(self.child.paint_insets());
+        size
+    }
+
+    #[instrument(name = "Presence", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+
+        let invalid = ctx.region().bounding_box();
+        for layer in self.cursors.values() {
+            if layer.bounds().intersect(invalid).area() == 0.0 {
+                continue;
+            }
+            let cursor = &layer.cursor;
+            for rect in &cursor.selection {
+                ctx.fill(*rect, &cursor.color.clone().with_alpha(0.3));
+            }
+
+            let x = cursor.position.x + CURSOR_WIDTH / 2.0;
+            let line = Line::new(
+                (x, cursor.position.y),
+                (x, cursor.position.y + CURSOR_HEIGHT),
+            );
+            ctx.stroke(line, &cursor.color, CURSOR_WIDTH);
+
+            let label_rect = layer.label_rect();
+            ctx.fill(label_rect, &cursor.color);
+            layer
+                .label
+                .draw(ctx, label_rect.origin() + (LABEL_PADDING, LABEL_PADDING));
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: format!("{} remote cursors", self.cursors.len()),
+            children: vec![self.child.widget().debug_state(data)],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::widget::SizedBox;
+    use crate::WidgetId;
+    use test_log::test;
+
+    #[test]
+    fn cursors_are_added_moved_and_removed() {
+        let id = WidgetId::next();
+        let widget = Presence::new(SizedBox::empty().width(200.).height(200.)).with_id(id);
+        let cursor = |x| RemoteCursor {
+            id: 7,
+            position: Point::new(x, 50.),
+            selection: Vec::new(),
+            label: "Ada".into(),
+            color: Color::rgb8(0x20, 0x80, 0xe0),
+        };
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            // The id belongs to the `IdentityWrapper` around the `Presence`.
+            let count = |harness: &mut Harness<()>| {
+                harness.get_debug_state(id).children[0].main_value.clone()
+            };
+            harness.submit_command(RemoteCursor::UPDATE.with(cursor(10.)).to(id));
+            harness.submit_command(RemoteCursor::UPDATE.with(cursor(90.)).to(id));
+            assert_eq!(count(harness), "1 remote cursors");
+            harness.submit_command(RemoteCursor::REMOVE.with(7).to(id));
+            assert_eq!(count(harness), "0 remote cursors");
+        });
+    }
+}
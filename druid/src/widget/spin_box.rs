@@ -0,0 +1,262 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text box for numbers, with a stepper.
+
+use std::rc::Rc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::text::{Formatter, ParseFormatter, Selection, Validation, ValidationError};
+use crate::widget::prelude::*;
+use crate::widget::value_textbox::COMPLETE_EDITING;
+use crate::widget::{Stepper, TextBox, ValueTextBox};
+use crate::{Point, WidgetPod};
+
+/// A numeric input: a text box for typing a value, next to a [`Stepper`] for
+/// stepping it up and down.
+///
+/// Holding down one of the stepper's buttons keeps changing the value. Typed
+/// values are parsed with the formatter set with [`with_formatter`], which is a
+/// [`ParseFormatter`] by default, and are only accepted if they are within the
+/// range set with [`with_range`]. Invalid input is selected when return is
+/// pressed, and discarded when the text box loses focus.
+///
+/// [`with_formatter`]: SpinBox::with_formatter
+/// [`with_range`]: SpinBox::with_range
+pub struct SpinBox {
+    text: WidgetPod<f64, ValueTextBox<f64>>,
+    stepper: WidgetPod<f64, Stepper>,
+    formatter: Rc<dyn Formatter<f64>>,
+    min: f64,
+    max: f64,
+    step: f64,
+    wrap: bool,
+}
+
+/// A [`Formatter`] that rejects values outside of a range.
+struct RangeFormatter {
+    inner: Rc<dyn Formatter<f64>>,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Debug)]
+struct OutOfRange {
+    min: f64,
+    max: f64,
+}
+
+impl SpinBox {
+    /// Create a new `SpinBox`.
+    pub fn new() -> Self {
+        SpinBox {
+            text: WidgetPod::new(TextBox::new().with_formatter(ParseFormatter::new())),
+            stepper: WidgetPod::new(Stepper::new()),
+            formatter: Rc::new(ParseFormatter::<f64>::new()),
+            min: f64::MIN,
+            max: f64::MAX,
+            step: 1.0,
+            wrap: false,
+        }
+        .rebuild()
+    }
+
+    /// Builder-style method to set the range of accepted values.
+    ///
+    /// The default range is `f64::MIN..=f64::MAX`.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self.rebuild()
+    }
+
+    /// Builder-style method to set the amount each step changes the value by.
+    ///
+    /// The default step is `1.0`.
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = step;
+        self.rebuild()
+    }
+
+    /// Builder-style method to set whether stepping past one end of the range
+    /// continues from the other. See [`Stepper::with_wraparound`].
+    ///
+    /// The default is `false`.
+    pub fn with_wraparound(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self.rebuild()
+    }
+
+    /// Builder-style method to set the [`Formatter`] used to show and parse
+    /// the value, such as a [`NumberFormatter`].
+    ///
+    /// [`NumberFormatter`]: crate::text::NumberFormatter
+    pub fn with_formatter(mut self, formatter: impl Formatter<f64> + 'static) -> Self {
+        self.formatter = Rc::new(formatter);
+        self.rebuild()
+    }
+
+    /// Recreate the children with the current settings.
+    fn rebuild(mut self) -> Self {
+        let formatter = RangeFormatter {
+            inner: self.formatter.clone(),
+            min: self.min,
+            max: self.max,
+        };
+        self.text = WidgetPod::new(TextBox::new().with_formatter(formatter));
+        self.stepper = WidgetPod::new(
+            Stepper::new()
+                .with_range(self.min, self.max)
+                .with_step(self.step)
+                .with_wraparound(self.wrap),
+        );
+        self
+    }
+}
+
+impl Default for SpinBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter<f64> for RangeFormatter {
+    fn format(&self, value: &f64) -> String {
+        self.inner.format(value)
+    }
+
+    fn format_for_editing(&self, value: &f64) -> String {
+        self.inner.format_for_editing(value)
+    }
+
+    fn validate_partial_input(&self, input: &str, sel: &Selection) -> Validation {
+        // Partial input can be out of range on its way to a valid value.
+        self.inner.validate_partial_input(input, sel)
+    }
+
+    fn value(&self, input: &str) -> Result<f64, ValidationError> {
+        let value = self.inner.value(input)?;
+        if value < self.min || value > self.max {
+            return Err(ValidationError::new(OutOfRange {
+                min: self.min,
+                max: self.max,
+            }));
+        }
+        Ok(value)
+    }
+}
+
+impl std::fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "must be between {} and {}", self.min, self.max)
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+impl Widget<f64> for SpinBox {
+    #[instrument(name = "SpinBox", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
+        if let Event::MouseDown(mouse) = event {
+            if self.text.has_focus() && self.stepper.layout_rect().contains(mouse.pos) {
+                // Step from the typed value rather than discarding it.
+                trace!("Completing edit before stepping");
+                let complete = Event::Command(COMPLETE_EDITING.to(self.text.id()));
+                self.text.event(ctx, &complete, data, env);
+                ctx.resign_focus();
+            }
+        }
+        self.text.event(ctx, event, data, env);
+        self.stepper.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "SpinBox", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &f64, env: &Env) {
+        self.text.lifecycle(ctx, event, data, env);
+        self.stepper.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "SpinBox",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, data: &f64, env: &Env) {
+        self.text.update(ctx, data, env);
+        self.stepper.update(ctx, data, env);
+    }
+
+    #[instrument(name = "SpinBox", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &f64, env: &Env) -> Size {
+        bc.debug_check("SpinBox");
+        let stepper_size = self.stepper.layout(ctx, &bc.loosen(), data, env);
+        let text_bc = bc.shrink((stepper_size.width, 0.0));
+        let text_size = self.text.layout(ctx, &text_bc, data, env);
+
+        let size = bc.constrain(Size::new(
+            text_size.width + stepper_size.width,
+            text_size.height.max(stepper_size.height),
+        ));
+        let text_y = (size.height - text_size.height) / 2.0;
+        let stepper_y = (size.height - stepper_size.height) / 2.0;
+        self.text.set_origin(ctx, Point::new(0.0, text_y));
+        self.stepper
+            .set_origin(ctx, Point::new(text_size.width, stepper_y));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "SpinBox", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &f64, env: &Env) {
+        self.text.paint(ctx, data, env);
+        self.stepper.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &f64) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            main_value: data.to_string(),
+            children: vec![
+                self.text.widget().debug_state(data),
+                self.stepper.widget().debug_state(data),
+            ],
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_log::test;
+
+    #[test]
+    fn typed_values_must_be_in_range() {
+        let formatter = RangeFormatter {
+            inner: Rc::new(ParseFormatter::<f64>::new()),
+            min: 0.0,
+            max: 10.0,
+        };
+        assert_eq!(formatter.value("2.5").unwrap(), 2.5);
+        assert!(formatter.value("12").is_err());
+        assert!(formatter.value("-1").is_err());
+        assert!(formatter.value("ten").is_err());
+        // Out of range input can still be typed on the way to a valid value.
+        assert!(!formatter
+            .validate_partial_input("100", &Selection::caret(3))
+            .is_err());
+    }
+}
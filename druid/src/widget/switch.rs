@@ -28,6 +28,12 @@ const SWITCH_PADDING: f64 = 3.;
 const SWITCH_WIDTH_RATIO: f64 = 2.75;
 
 /// A switch that toggles a `bool`.
+///
+/// The switch is toggled by clicking it, or by dragging its knob past the
+/// middle. The colors of its track and knob are set with the `SWITCH_*` keys
+/// of the [`theme`].
+///
+/// [`theme`]: crate::theme
 #[derive(Debug, Clone)]
 pub struct Switch {
     knob_pos: Point,
@@ -255,7 +261,6 @@ impl Widget<bool> for Switch {
 
         // paint different background for on and off state
         // opacity of background color depends on knob position
-        // todo: make color configurable
         let opacity = if ctx.is_disabled() {
             0.0
         } else {
@@ -266,16 +271,18 @@ impl Widget<bool> for Switch {
             UnitPoint::TOP,
             UnitPoint::BOTTOM,
             (
-                env.get(theme::PRIMARY_LIGHT).with_alpha(opacity),
-                env.get(theme::PRIMARY_DARK).with_alpha(opacity),
+                env.get(theme::SWITCH_TRACK_ON_LIGHT).with_alpha(opacity),
+                env.get(theme::SWITCH_TRACK_ON_DARK).with_alpha(opacity),
             ),
         );
         let background_gradient_off_state = LinearGradient::new(
             UnitPoint::TOP,
             UnitPoint::BOTTOM,
             (
-                env.get(theme::BACKGROUND_LIGHT).with_alpha(1. - opacity),
-                env.get(theme::BACKGROUND_DARK).with_alpha(1. - opacity),
+                env.get(theme::SWITCH_TRACK_OFF_LIGHT)
+                    .with_alpha(1. - opacity),
+                env.get(theme::SWITCH_TRACK_OFF_DARK)
+                    .with_alpha(1. - opacity),
             ),
         );
 
@@ -302,8 +309,8 @@ impl Widget<bool> for Switch {
                 UnitPoint::TOP,
                 UnitPoint::BOTTOM,
                 (
-                    env.get(theme::FOREGROUND_DARK),
-                    env.get(theme::FOREGROUND_LIGHT),
+                    env.get(theme::SWITCH_KNOB_DARK),
+                    env.get(theme::SWITCH_KNOB_LIGHT),
                 ),
             )
         } else {
@@ -311,8 +318,8 @@ impl Widget<bool> for Switch {
                 UnitPoint::TOP,
                 UnitPoint::BOTTOM,
                 (
-                    env.get(theme::FOREGROUND_LIGHT),
-                    env.get(theme::FOREGROUND_DARK),
+                    env.get(theme::SWITCH_KNOB_LIGHT),
+                    env.get(theme::SWITCH_KNOB_DARK),
                 ),
             )
         };
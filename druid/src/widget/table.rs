@@ -0,0 +1,656 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A table widget with sortable, resizable columns.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::widget::prelude::*;
+use crate::widget::ListIter;
+use crate::{
+    theme, ArcStr, Cursor, InternalLifeCycle, MouseButton, Point, Rect, TextLayout, WidgetPod,
+};
+
+/// The default height of the header and of each row.
+const DEFAULT_ROW_HEIGHT: f64 = 24.0;
+/// The default width of a column.
+const DEFAULT_COLUMN_WIDTH: f64 = 100.0;
+/// How close the pointer has to be to the edge of a column to resize it.
+const RESIZE_HANDLE_WIDTH: f64 = 4.0;
+/// Rows kept alive beyond each end of the visible area, so that small scrolls
+/// don't need new widgets.
+const OVERSCAN_ROWS: usize = 4;
+/// Horizontal padding around the column titles.
+const HEADER_PADDING: f64 = 6.0;
+
+type CellPod<R> = WidgetPod<R, Box<dyn Widget<R>>>;
+
+/// The direction a [`Table`] is sorted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Smallest first.
+    Ascending,
+    /// Largest first.
+    Descending,
+}
+
+/// A column of a [`Table`].
+pub struct TableColumn<R> {
+    title: TextLayout<ArcStr>,
+    width: f64,
+    min_width: f64,
+    make_cell: Box<dyn Fn() -> Box<dyn Widget<R>>>,
+    compare: Option<Box<dyn Fn(&R, &R) -> Ordering>>,
+}
+
+/// A table of rows, with a header that stays in view.
+///
+/// Each row of the table is an item of a collection implementing [`ListIter`],
+/// and each [`TableColumn`] builds the widgets that show a part of a row; use a
+/// lens to focus a cell on a single field.
+///
+/// Columns can be resized by dragging the edges of their titles, and columns
+/// with a comparator can be sorted by clicking their title. Sorting only
+/// changes the order in which rows are displayed; the data is left untouched.
+///
+/// Put the table in a vertical [`Scroll`] to show long collections. The table
+/// only creates widgets for the rows that are close to the visible area, and
+/// only visits those rows when handling events, so it stays fast with many
+/// thousands of rows as long as the collection overrides
+/// [`ListIter::for_index`] and [`ListIter::for_index_mut`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{Label, Scroll, Table, TableColumn};
+/// use druid::{Data, Lens, Widget, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Planet {
+///     name: String,
+///     moons: u32,
+/// }
+///
+/// fn planet_table() -> impl Widget<Arc<Vec<Planet>>> {
+///     let table = Table::new()
+///         .with_column(TableColumn::new("Name", || Label::raw().lens(Planet::name)))
+///         .with_column(
+///             TableColumn::new("Moons", || {
+///                 Label::dynamic(|moons: &u32, _| moons.to_string()).lens(Planet::moons)
+///             })
+///             .sort_by(|a: &Planet, b: &Planet| a.moons.cmp(&b.moons)),
+///         );
+///     Scroll::new(table).vertical()
+/// }
+/// ```
+///
+/// [`Scroll`]: super::Scroll
+pub struct Table<R> {
+    columns: Vec<TableColumn<R>>,
+    row_height: f64,
+    sort: Option<(usize, SortDirection)>,
+    /// Data indices, in the order they are displayed.
+    order: Vec<usize>,
+    /// The display position of each data index; the inverse of `order`.
+    positions: Vec<usize>,
+    /// The cells of the rows that currently have widgets, by data index.
+    rows: BTreeMap<usize, Vec<CellPod<R>>>,
+    /// The visible part of the table, in our own coordinates.
+    clip: Option<Rect>,
+    /// The column being resized, with the pointer position and column width
+    /// when the drag started.
+    resizing: Option<(usize, f64, f64)>,
+}
+
+impl<R: Data> TableColumn<R> {
+    /// Create a new column with the given title.
+    ///
+    /// The closure is called to create the cell widget whenever a row of this
+    /// column comes into view.
+    pub fn new<W: Widget<R> + 'static>(
+        title: impl Into<ArcStr>,
+        make_cell: impl Fn() -> W + 'static,
+    ) -> Self {
+        TableColumn {
+            title: TextLayout::from_text(title),
+            width: DEFAULT_COLUMN_WIDTH,
+            min_width: 2.0 * RESIZE_HANDLE_WIDTH,
+            make_cell: Box::new(move || Box::new(make_cell())),
+            compare: None,
+        }
+    }
+
+    /// Builder-style method to set the initial width of the column.
+    pub fn with_width(mut self, width: f64) -> Self {
+        self.width = width.max(self.min_width);
+        self
+    }
+
+    /// Builder-style method to set the width below which the user can't resize the column.
+    pub fn with_min_width(mut self, min_width: f64) -> Self {
+        self.min_width = min_width;
+        self.width = self.width.max(min_width);
+        self
+    }
+
+    /// Builder-style method to make the column sortable, using `compare` to order rows.
+    pub fn sort_by(mut self, compare: impl Fn(&R, &R) -> Ordering + 'static) -> Self {
+        self.compare = Some(Box::new(compare));
+        self
+    }
+
+    /// The current width of the column.
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+}
+
+impl<R: Data> Table<R> {
+    /// Create a new table without any columns.
+    pub fn new() -> Self {
+        Table {
+            columns: Vec::new(),
+            row_height: DEFAULT_ROW_HEIGHT,
+            sort: None,
+            order: Vec::new(),
+            positions: Vec::new(),
+            rows: BTreeMap::new(),
+            clip: None,
+            resizing: None,
+        }
+    }
+
+    /// Builder-style method to add a column.
+    pub fn with_column(mut self, column: TableColumn<R>) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Builder-style method to set the height of the header and of each row.
+    pub fn with_row_height(mut self, height: f64) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    /// Builder-style method to sort the table by a column initially.
+    ///
+    /// This has no effect if the column has no comparator.
+    pub fn with_sort(mut self, column: usize, direction: SortDirection) -> Self {
+        self.sort = Some((column, direction));
+        self
+    }
+
+    /// The column the table is sorted by, and in which direction.
+    pub fn sort(&self) -> Option<(usize, SortDirection)> {
+        self.sort
+    }
+
+    /// The columns of the table.
+    pub fn columns(&self) -> &[TableColumn<R>] {
+        &self.columns
+    }
+
+    /// Recompute the display order from the data and the current sort.
+    fn resort(&mut self, data: &impl ListIter<R>) {
+        let len = data.data_len();
+        self.order = (0..len).collect();
+        let compare = self
+            .sort
+            .and_then(|(column, direction)| Some((self.columns.get(column)?, direction)))
+            .and_then(|(column, direction)| Some((column.compare.as_ref()?, direction)));
+        if let Some((compare, direction)) = compare {
+            let mut rows = Vec::with_capacity(len);
+            data.for_each(|row, _| rows.push(row.clone()));
+            // A stable sort, so rows that compare equal keep their order in the data.
+            self.order.sort_by(|&a, &b| {
+                let ordering = compare(&rows[a], &rows[b]);
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+        self.positions = vec![0; len];
+        for (position, &idx) in self.order.iter().enumerate() {
+            self.positions[idx] = position;
+        }
+    }
+
+    /// The display positions of the rows that should have widgets.
+    fn visible_rows(&self) -> Range<usize> {
+        let clip = match self.clip {
+            Some(clip) => clip,
+            None => return 0..0,
+        };
+        let len = self.order.len();
+        // The header is as tall as a row, and rows start below it.
+        let first = (clip.y0 / self.row_height - 1.0).floor().max(0.0) as usize;
+        let last = (clip.y1 / self.row_height - 1.0).ceil().max(0.0) as usize;
+        first.saturating_sub(OVERSCAN_ROWS).min(len)..(last + OVERSCAN_ROWS).min(len)
+    }
+
+    /// Create and remove cell widgets, so that only the rows near the visible area have them.
+    ///
+    /// Returns `true` if any rows were added or removed.
+    fn realize_rows(&mut self) -> bool {
+        let visible = self.visible_rows();
+        let before = self.rows.len();
+        let positions = &self.positions;
+        self.rows.retain(|idx, _| {
+            positions
+                .get(*idx)
+                .map_or(false, |position| visible.contains(position))
+        });
+        let mut changed = self.rows.len() != before;
+        for &idx in &self.order[visible] {
+            if !self.rows.contains_key(&idx) {
+                let cells = self
+                    .columns
+                    .iter()
+                    .map(|column| WidgetPod::new((column.make_cell)()))
+                    .collect();
+                self.rows.insert(idx, cells);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// The top of the header, which stays at the top of the visible area.
+    fn header_y(&self) -> f64 {
+        self.clip.map_or(0.0, |clip| clip.y0.max(0.0))
+    }
+
+    fn in_header(&self, pos: Point) -> bool {
+        let top = self.header_y();
+        pos.y >= top && pos.y < top + self.row_height
+    }
+
+    /// Find the column at `x`, and whether `x` is on the handle for resizing it.
+    fn hit_column(&self, x: f64) -> Option<(usize, bool)> {
+        let mut right = 0.0;
+        for (i, column) in self.columns.iter().enumerate() {
+            right += column.width;
+            if (x - right).abs() <= RESIZE_HANDLE_WIDTH {
+                return Some((i, true));
+            }
+            if x < right {
+                return Some((i, false));
+            }
+        }
+        None
+    }
+
+    fn paint_header(&self, ctx: &mut PaintCtx, env: &Env) {
+        let top = self.header_y();
+        let header = Rect::new(0.0, top, ctx.size().width, top + self.row_height);
+        let border_color = env.get(theme::BORDER_DARK);
+        ctx.fill(header, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(
+            Line::new((header.x0, header.y1 - 0.5), (header.x1, header.y1 - 0.5)),
+            &border_color,
+            1.0,
+        );
+
+        let mut x = 0.0;
+        for (i, column) in self.columns.iter().enumerate() {
+            let text_y = top + (self.row_height - column.title.size().height) / 2.0;
+            ctx.with_save(|ctx| {
+                ctx.clip(Rect::new(x, top, x + column.width, header.y1));
+                column.title.draw(ctx, (x + HEADER_PADDING, text_y));
+            });
+
+            let sorted = self
+                .sort
+                .filter(|(sorted, _)| *sorted == i && column.compare.is_some());
+            if let Some((_, direction)) = sorted {
+                let center = Point::new(
+                    x + column.width - HEADER_PADDING - 4.0,
+                    top + self.row_height / 2.0,
+                );
+                let (tip, base) = match direction {
+                    SortDirection::Ascending => (-3.0, 3.0),
+                    SortDirection::Descending => (3.0, -3.0),
+                };
+                let mut arrow = BezPath::new();
+                arrow.move_to((center.x - 4.0, center.y + base));
+                arrow.line_to((center.x + 4.0, center.y + base));
+                arrow.line_to((center.x, center.y + tip));
+                arrow.close_path();
+                ctx.fill(arrow, &env.get(theme::TEXT_COLOR));
+            }
+
+            x += column.width;
+            ctx.stroke(
+                Line::new((x - 0.5, top), (x - 0.5, header.y1)),
+                &border_color,
+                1.0,
+            );
+        }
+    }
+}
+
+impl<R: Data> Default for Table<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Data, L: ListIter<R>> Widget<L> for Table<R> {
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut L, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if self.in_header(mouse.pos) => {
+                let hit = if mouse.button == MouseButton::Left {
+                    self.hit_column(mouse.pos.x)
+                } else {
+                    None
+                };
+                match hit {
+                    Some((column, true)) => {
+                        self.resizing = Some((column, mouse.pos.x, self.columns[column].width));
+                        ctx.set_active(true);
+                    }
+                    Some((column, false)) if self.columns[column].compare.is_some() => {
+                        let direction = match self.sort {
+                            Some((sorted, SortDirection::Ascending)) if sorted == column => {
+                                SortDirection::Descending
+                            }
+                            _ => SortDirection::Ascending,
+                        };
+                        self.sort = Some((column, direction));
+                        self.resort(&*data);
+                        if self.realize_rows() {
+                            ctx.children_changed();
+                        }
+                        ctx.request_layout();
+                        ctx.request_paint();
+                    }
+                    _ => (),
+                }
+                // Cells under the header are hidden, so they don't get any clicks.
+                ctx.set_handled();
+                return;
+            }
+            Event::MouseMove(mouse) => {
+                if let Some((column, start_x, start_width)) = self.resizing {
+                    let column = &mut self.columns[column];
+                    column.width = (start_width + mouse.pos.x - start_x).max(column.min_width);
+                    ctx.request_layout();
+                    ctx.request_paint();
+                    ctx.set_handled();
+                    return;
+                }
+                if self.in_header(mouse.pos)
+                    && matches!(self.hit_column(mouse.pos.x), Some((_, true)))
+                {
+                    ctx.set_cursor(&Cursor::ResizeLeftRight);
+                } else {
+                    ctx.clear_cursor();
+                }
+            }
+            Event::MouseUp(_) if self.resizing.is_some() => {
+                self.resizing = None;
+                ctx.set_active(false);
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+
+        for (&idx, cells) in &mut self.rows {
+            data.for_index_mut(idx, |row| {
+                for cell in cells.iter_mut().filter(|cell| cell.is_initialized()) {
+                    cell.event(ctx, event, row, env);
+                }
+            });
+        }
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &L, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.resort(data),
+            LifeCycle::ViewContextChanged(view_context) => {
+                self.clip = Some(view_context.clip);
+                if self.realize_rows() {
+                    ctx.children_changed();
+                    ctx.request_paint();
+                }
+            }
+            _ => (),
+        }
+
+        // Rows that were just realized only get to see `WidgetAdded`.
+        let adding = matches!(
+            event,
+            LifeCycle::WidgetAdded | LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded)
+        );
+        for (&idx, cells) in &mut self.rows {
+            data.for_index(idx, |row| {
+                for cell in cells.iter_mut() {
+                    if adding || cell.is_initialized() {
+                        cell.lifecycle(ctx, event, row, env);
+                    }
+                }
+            });
+        }
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &L, data: &L, env: &Env) {
+        // Update the existing cells before realizing new rows, which must get
+        // `WidgetAdded` first.
+        for (&idx, cells) in &mut self.rows {
+            data.for_index(idx, |row| {
+                for cell in cells.iter_mut().filter(|cell| cell.is_initialized()) {
+                    cell.update(ctx, row, env);
+                }
+            });
+        }
+
+        if !old_data.same(data) {
+            let old_order = std::mem::take(&mut self.order);
+            self.resort(data);
+            if self.order != old_order {
+                if self.realize_rows() {
+                    ctx.children_changed();
+                }
+                ctx.request_layout();
+            }
+        }
+
+        for column in &mut self.columns {
+            if column.title.needs_rebuild_after_update(ctx) {
+                ctx.request_layout();
+            }
+        }
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &L, env: &Env) -> Size {
+        bc.debug_check("Table");
+
+        for column in &mut self.columns {
+            column.title.rebuild_if_needed(ctx.text(), env);
+        }
+
+        let row_height = self.row_height;
+        let columns = &self.columns;
+        let positions = &self.positions;
+        for (&idx, cells) in &mut self.rows {
+            data.for_index(idx, |row| {
+                let y = row_height * (positions[idx] + 1) as f64;
+                let mut x = 0.0;
+                for (cell, column) in cells.iter_mut().zip(columns) {
+                    if cell.is_initialized() {
+                        let cell_bc = BoxConstraints::tight(Size::new(column.width, row_height));
+                        cell.layout(ctx, &cell_bc, row, env);
+                        cell.set_origin(ctx, Point::new(x, y));
+                    }
+                    x += column.width;
+                }
+            });
+        }
+
+        let width: f64 = self.columns.iter().map(|column| column.width).sum();
+        let height = row_height * (self.order.len() + 1) as f64;
+        let size = bc.constrain(Size::new(width, height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Table", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &L, env: &Env) {
+        for (&idx, cells) in &mut self.rows {
+            data.for_index(idx, |row| {
+                // Rows realized since the last layout are painted after the next one.
+                for cell in cells.iter_mut() {
+                    if cell.is_initialized() && !cell.layout_requested() {
+                        cell.paint(ctx, row, env);
+                    }
+                }
+            });
+        }
+        self.paint_header(ctx, env);
+    }
+
+    fn debug_state(&self, data: &L) -> DebugState {
+        let mut children = Vec::new();
+        for (&idx, cells) in &self.rows {
+            data.for_index(idx, |row| {
+                children.extend(cells.iter().map(|cell| cell.widget().debug_state(row)));
+            });
+        }
+
+        DebugState {
+            display_name: "Table".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Label, Scroll, SizedBox};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_log::test;
+
+    fn number_table() -> Table<u32> {
+        Table::new().with_column(
+            TableColumn::new("Number", || Label::dynamic(|n: &u32, _| n.to_string()))
+                .sort_by(|a: &u32, b: &u32| a.cmp(b)),
+        )
+    }
+
+    #[test]
+    fn sorting_is_stable_and_reversible() {
+        let data = Arc::new(vec![3, 1, 2, 1]);
+        let mut table = number_table().with_sort(0, SortDirection::Ascending);
+        table.resort(&data);
+        assert_eq!(table.order, vec![1, 3, 2, 0]);
+        assert_eq!(table.positions, vec![3, 0, 2, 1]);
+
+        table.sort = Some((0, SortDirection::Descending));
+        table.resort(&data);
+        assert_eq!(table.order, vec![0, 2, 1, 3]);
+
+        table.sort = None;
+        table.resort(&data);
+        assert_eq!(table.order, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn hit_column_finds_resize_handles() {
+        let table = number_table()
+            .with_column(TableColumn::new("Other", || Label::new("")).with_width(50.0));
+        assert_eq!(table.hit_column(10.0), Some((0, false)));
+        assert_eq!(table.hit_column(98.0), Some((0, true)));
+        assert_eq!(table.hit_column(103.0), Some((0, true)));
+        assert_eq!(table.hit_column(120.0), Some((1, false)));
+        assert_eq!(table.hit_column(200.0), None);
+    }
+
+    #[test]
+    fn only_visible_rows_get_widgets() {
+        let created = Rc::new(Cell::new(0));
+        let created_clone = created.clone();
+        let table = Table::new().with_column(TableColumn::new("Number", move || {
+            created_clone.set(created_clone.get() + 1);
+            Label::dynamic(|n: &u32, _| n.to_string())
+        }));
+        let data = Arc::new((0..10_000).collect::<Vec<u32>>());
+        let widget = SizedBox::new(Scroll::new(table).vertical()).height(240.0);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.paint();
+
+            // Only a screenful of rows, plus overscan, out of ten thousand.
+            assert!(created.get() > 0);
+            assert!(created.get() < 50);
+        });
+    }
+
+    /// A row that counts how often it is cloned.
+    struct CountedRow(Rc<Cell<usize>>);
+
+    impl Clone for CountedRow {
+        fn clone(&self) -> Self {
+            self.0.set(self.0.get() + 1);
+            CountedRow(self.0.clone())
+        }
+    }
+
+    impl Data for CountedRow {
+        fn same(&self, _other: &Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn events_only_clone_realized_rows() {
+        let clones = Rc::new(Cell::new(0));
+        let table = Table::new().with_column(TableColumn::new("Empty", SizedBox::empty));
+        let data = Arc::new(
+            (0..10_000)
+                .map(|_| CountedRow(clones.clone()))
+                .collect::<Vec<_>>(),
+        );
+        let widget = SizedBox::new(Scroll::new(table).vertical()).height(240.0);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            clones.set(0);
+            harness.event(Event::MouseMove(move_mouse((10., 100.))));
+            assert!(clones.get() > 0);
+            assert!(clones.get() < 50);
+        });
+    }
+}
@@ -701,6 +701,16 @@ impl<T: TextStorage + EditableText> Widget<T> for TextBox<T> {
         {
             ctx.request_layout();
         }
+        if ctx.env_key_changed(&theme::WINDOW_IS_ACTIVE) && ctx.has_focus() {
+            // Only blink the cursor in the active window.
+            if env.get(theme::WINDOW_IS_ACTIVE) {
+                self.reset_cursor_blink(ctx.request_timer(CURSOR_BLINK_DURATION));
+            } else {
+                self.cursor_on = false;
+                self.cursor_timer = TimerToken::INVALID;
+            }
+            ctx.request_paint();
+        }
         if self.text().can_write() {
             if let Some(ime_invalidation) = self.text_mut().borrow_mut().pending_ime_invalidation()
             {
@@ -0,0 +1,653 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A row of tool buttons that moves the ones that don't fit into a menu.
+
+use std::rc::Rc;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::{BezPath, Line};
+use crate::widget::popup;
+use crate::widget::prelude::*;
+use crate::{theme, Lens, LensExt, MouseButton, Point, Rect, Selector, TextLayout, WidgetPod};
+
+/// The padding between the edge of a button and its icon.
+const ITEM_PADDING: f64 = 4.0;
+/// The width of a separator.
+const SEPARATOR_WIDTH: f64 = 9.0;
+/// The width of the button that opens the overflow menu.
+const CHEVRON_WIDTH: f64 = 20.0;
+/// The width of the column for check marks in the overflow menu.
+const MENU_CHECK_WIDTH: f64 = 20.0;
+
+/// Sent from the overflow menu to the toolbar when an item is picked.
+const ACTIVATE_ITEM: Selector<usize> = Selector::new("druid-builtin.toolbar.activate-item");
+
+type ClickCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+type IconPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+
+/// A row of icon buttons, toggle buttons and separators, as found at the
+/// top of many application windows.
+///
+/// When the window is too narrow for all of the items, the ones at the end
+/// are moved into a menu, opened with a chevron button. Each button has a
+/// label, which is shown in that menu.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, Toolbar};
+/// use druid::{Data, Lens};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Editor {
+///     bold: bool,
+///     saved: bool,
+/// }
+///
+/// let toolbar = Toolbar::new()
+///     .with_button(Label::new("S"), "Save", |_ctx, data: &mut Editor, _env| data.saved = true)
+///     .with_separator()
+///     .with_toggle(Label::new("B"), "Bold", Editor::bold);
+/// ```
+pub struct Toolbar<T> {
+    // The overflow menu needs a pod of its own to sync with; see the `popup` module.
+    inner: WidgetPod<T, ToolbarRow<T>>,
+}
+
+struct ToolbarRow<T> {
+    items: Vec<Item<T>>,
+    /// The number of items shown in the row; the rest are in the overflow menu.
+    visible: usize,
+    chevron: Rect,
+    hot: Option<Hit>,
+    pressed: Option<Hit>,
+}
+
+struct Item<T> {
+    kind: ItemKind<T>,
+    label: String,
+    icon: Option<IconPod<T>>,
+    rect: Rect,
+}
+
+enum ItemKind<T> {
+    Button(ClickCallback<T>),
+    Toggle {
+        is_on: Rc<dyn Fn(&T) -> bool>,
+        toggle: Box<dyn Fn(&mut T)>,
+    },
+    Separator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Hit {
+    Item(usize),
+    Chevron,
+}
+
+/// The menu with the items that don't fit in the toolbar.
+struct OverflowMenu<T> {
+    entries: Vec<OverflowEntry<T>>,
+    /// The toolbar to send the picked item to.
+    target: WidgetId,
+    hot: Option<usize>,
+}
+
+struct OverflowEntry<T> {
+    item: usize,
+    label: TextLayout<String>,
+    is_on: Option<Rc<dyn Fn(&T) -> bool>>,
+}
+
+impl<T: Data> Toolbar<T> {
+    /// Create a new, empty `Toolbar`.
+    pub fn new() -> Self {
+        Toolbar {
+            inner: WidgetPod::new(ToolbarRow {
+                items: Vec::new(),
+                visible: 0,
+                chevron: Rect::ZERO,
+                hot: None,
+                pressed: None,
+            }),
+        }
+    }
+
+    /// Builder-style method to add a button showing `icon`, which calls
+    /// `on_click` when it is clicked.
+    ///
+    /// The `label` is shown instead of the icon in the overflow menu.
+    pub fn with_button(
+        mut self,
+        icon: impl Widget<T> + 'static,
+        label: impl Into<String>,
+        on_click: impl Fn(&mut EventCtx, &mut T, &Env) + 'static,
+    ) -> Self {
+        let icon = WidgetPod::new(icon).boxed();
+        self.add_item(
+            ItemKind::Button(Box::new(on_click)),
+            label.into(),
+            Some(icon),
+        );
+        self
+    }
+
+    /// Builder-style method to add a button showing `icon` that toggles the
+    /// `bool` that `lens` points to, and is shown as pressed while it is `true`.
+    ///
+    /// The `label` is shown instead of the icon in the overflow menu.
+    pub fn with_toggle(
+        mut self,
+        icon: impl Widget<T> + 'static,
+        label: impl Into<String>,
+        lens: impl Lens<T, bool> + Clone + 'static,
+    ) -> Self {
+        let toggle_lens = lens.clone();
+        let kind = ItemKind::Toggle {
+            is_on: Rc::new(move |data: &T| lens.get(data)),
+            toggle: Box::new(move |data: &mut T| toggle_lens.with_mut(data, |on| *on = !*on)),
+        };
+        self.add_item(kind, label.into(), Some(WidgetPod::new(icon).boxed()));
+        self
+    }
+
+    /// Builder-style method to add a separator between groups of buttons.
+    pub fn with_separator(mut self) -> Self {
+        self.add_item(ItemKind::Separator, String::new(), None);
+        self
+    }
+
+    fn add_item(&mut self, kind: ItemKind<T>, label: String, icon: Option<IconPod<T>>) {
+        self.inner.widget_mut().items.push(Item {
+            kind,
+            label,
+            icon,
+            rect: Rect::ZERO,
+        });
+    }
+}
+
+impl<T: Data> Default for Toolbar<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of items, with the given widths, that fit in `available` width.
+///
+/// When not all of them fit, room is left for the overflow button.
+fn fitting_items(widths: &[f64], chevron: f64, available: f64) -> usize {
+    if widths.iter().sum::<f64>() <= available {
+        return widths.len();
+    }
+    let mut used = chevron;
+    widths
+        .iter()
+        .take_while(|width| {
+            used += *width;
+            used <= available
+        })
+        .count()
+}
+
+impl<T: Data> ToolbarRow<T> {
+    fn hit_at(&self, pos: Point) -> Option<Hit> {
+        if self.visible < self.items.len() && self.chevron.contains(pos) {
+            return Some(Hit::Chevron);
+        }
+        self.items[..self.visible]
+            .iter()
+            .position(|item| item.icon.is_some() && item.rect.contains(pos))
+            .map(Hit::Item)
+    }
+
+    fn activate(&mut self, ctx: &mut EventCtx, data: &mut T, idx: usize, env: &Env) {
+        let item = &self.items[idx];
+        trace!("Activating toolbar item {:?}", item.label);
+        match &item.kind {
+            ItemKind::Button(on_click) => on_click(ctx, data, env),
+            ItemKind::Toggle { toggle, .. } => toggle(data),
+            ItemKind::Separator => (),
+        }
+    }
+
+    fn open_overflow_menu(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        let entries: Vec<OverflowEntry<T>> = self.items[self.visible..]
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.icon.is_some())
+            .map(|(n, item)| OverflowEntry {
+                item: self.visible + n,
+                label: TextLayout::from_text(item.label.clone()),
+                is_on: match &item.kind {
+                    ItemKind::Toggle { is_on, .. } => Some(is_on.clone()),
+                    _ => None,
+                },
+            })
+            .collect();
+        let longest = entries
+            .iter()
+            .map(|entry| {
+                let mut label = entry.label.clone();
+                label.rebuild_if_needed(ctx.text(), env);
+                label.size().width
+            })
+            .fold(0.0, f64::max);
+        let width = MENU_CHECK_WIDTH + longest + ITEM_PADDING * 2.0;
+        let size = Size::new(width, popup::ROW_HEIGHT * entries.len() as f64);
+        let origin = Point::new(self.chevron.x0, ctx.size().height);
+        let config = popup::config(ctx, origin, size);
+        let menu = OverflowMenu {
+            entries,
+            target: ctx.widget_id(),
+            hot: None,
+        };
+        ctx.new_sub_window(config, menu, data.clone(), env.clone());
+    }
+}
+
+impl<T: Data> Widget<T> for Toolbar<T> {
+    #[instrument(name = "Toolbar", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.inner.event(ctx, event, data, env);
+    }
+
+    #[instrument(name = "Toolbar", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env);
+    }
+
+    #[instrument(
+        name = "Toolbar",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.inner.update(ctx, data, env);
+    }
+
+    #[instrument(name = "Toolbar", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        self.inner.set_origin(ctx, Point::ORIGIN);
+        size
+    }
+
+    #[instrument(name = "Toolbar", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.inner.paint(ctx, data, env);
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        self.inner.widget().debug_state(data)
+    }
+}
+
+impl<T: Data> Widget<T> for ToolbarRow<T> {
+    #[instrument(
+        name = "ToolbarRow",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // Icons in the overflow menu have no size, so they don't get pointer
+        // events, but their pods still notice when the pointer leaves them.
+        for item in &mut self.items {
+            if let Some(icon) = item.icon.as_mut() {
+                icon.event(ctx, event, data, env);
+            }
+        }
+
+        match event {
+            Event::MouseMove(mouse) => {
+                let hot = self.hit_at(mouse.pos);
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if !ctx.is_disabled() {
+                    self.pressed = self.hit_at(mouse.pos);
+                    ctx.set_active(self.pressed.is_some());
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button == MouseButton::Left && ctx.is_active() => {
+                ctx.set_active(false);
+                let pressed = self.pressed.take();
+                if pressed.is_some() && pressed == self.hit_at(mouse.pos) {
+                    match pressed {
+                        Some(Hit::Item(idx)) => self.activate(ctx, data, idx, env),
+                        Some(Hit::Chevron) => self.open_overflow_menu(ctx, data, env),
+                        None => (),
+                    }
+                }
+                ctx.request_paint();
+            }
+            Event::Command(cmd) if cmd.is(ACTIVATE_ITEM) => {
+                let idx = *cmd.get_unchecked(ACTIVATE_ITEM);
+                self.activate(ctx, data, idx, env);
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "ToolbarRow",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::HotChanged(false) = event {
+            self.hot = None;
+            ctx.request_paint();
+        }
+        for item in &mut self.items {
+            if let Some(icon) = item.icon.as_mut() {
+                icon.lifecycle(ctx, event, data, env);
+            }
+        }
+    }
+
+    #[instrument(
+        name = "ToolbarRow",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        for item in &mut self.items {
+            if let ItemKind::Toggle { is_on, .. } = &item.kind {
+                if is_on(old_data) != is_on(data) {
+                    ctx.request_paint();
+                }
+            }
+            if let Some(icon) = item.icon.as_mut() {
+                icon.update(ctx, data, env);
+            }
+        }
+    }
+
+    #[instrument(name = "ToolbarRow", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Toolbar");
+        let mut height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let mut widths = Vec::with_capacity(self.items.len());
+        for item in &mut self.items {
+            let width = match item.icon.as_mut() {
+                Some(icon) => {
+                    let icon_size = icon.layout(ctx, &bc.loosen(), data, env);
+                    height = height.max(icon_size.height + ITEM_PADDING * 2.0);
+                    icon_size.width + ITEM_PADDING * 2.0
+                }
+                None => SEPARATOR_WIDTH,
+            };
+            widths.push(width);
+        }
+
+        self.visible = fitting_items(&widths, CHEVRON_WIDTH, bc.max().width);
+        if self.visible < self.items.len() {
+            // Don't end the row with separators.
+            while self.visible > 0 && self.items[self.visible - 1].icon.is_none() {
+                self.visible -= 1;
+            }
+        }
+
+        let mut x = 0.0;
+        for (idx, (item, width)) in self.items.iter_mut().zip(widths).enumerate() {
+            item.rect = Rect::new(x, 0.0, x + width, height);
+            if let Some(icon) = item.icon.as_mut() {
+                if idx >= self.visible {
+                    icon.layout(ctx, &BoxConstraints::tight(Size::ZERO), data, env);
+                }
+                let icon_size = icon.layout_rect().size();
+                let origin = item.rect.center() - icon_size.to_vec2() / 2.0;
+                icon.set_origin(ctx, origin);
+            }
+            if idx < self.visible {
+                x += width;
+            }
+        }
+        self.chevron = if self.visible < self.items.len() {
+            Rect::new(x, 0.0, x + CHEVRON_WIDTH, height)
+        } else {
+            Rect::ZERO
+        };
+
+        let size = bc.constrain(Size::new(x + self.chevron.width(), height));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "ToolbarRow", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let radius = env.get(theme::BUTTON_BORDER_RADIUS);
+        let is_disabled = ctx.is_disabled();
+        for (idx, item) in self.items[..self.visible].iter_mut().enumerate() {
+            let rect = item.rect;
+            let is_on = match &item.kind {
+                ItemKind::Toggle { is_on, .. } => is_on(data),
+                ItemKind::Separator => {
+                    let x = rect.center().x.round() + 0.5;
+                    let line = Line::new((x, rect.y0 + ITEM_PADDING), (x, rect.y1 - ITEM_PADDING));
+                    ctx.stroke(line, &env.get(theme::BORDER_DARK), 1.0);
+                    continue;
+                }
+                ItemKind::Button(_) => false,
+            };
+            let background = if is_on || self.pressed == Some(Hit::Item(idx)) {
+                Some(theme::BUTTON_DARK)
+            } else if self.hot == Some(Hit::Item(idx)) && !is_disabled {
+                Some(theme::BACKGROUND_LIGHT)
+            } else {
+                None
+            };
+            if let Some(background) = background {
+                ctx.fill(rect.to_rounded_rect(radius), &env.get(background));
+            }
+            if is_on {
+                let border = rect.inset(-0.5).to_rounded_rect(radius);
+                ctx.stroke(border, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+            if let Some(icon) = item.icon.as_mut() {
+                icon.paint(ctx, data, env);
+            }
+        }
+
+        if self.visible < self.items.len() {
+            if self.hot == Some(Hit::Chevron) && !is_disabled {
+                ctx.fill(
+                    self.chevron.to_rounded_rect(radius),
+                    &env.get(theme::BACKGROUND_LIGHT),
+                );
+            }
+            let center = self.chevron.center();
+            let mut chevron = BezPath::new();
+            chevron.move_to((center.x - 4.0, center.y - 2.0));
+            chevron.line_to((center.x, center.y + 2.0));
+            chevron.line_to((center.x + 4.0, center.y - 2.0));
+            ctx.stroke(chevron, &env.get(theme::TEXT_COLOR), 1.5);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: "Toolbar".to_string(),
+            main_value: format!("{} of {} items shown", self.visible, self.items.len()),
+            children: self.items[..self.visible]
+                .iter()
+                .filter_map(|item| item.icon.as_ref())
+                .map(|icon| icon.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for OverflowMenu<T> {
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        popup::event(ctx, event);
+        match event {
+            Event::MouseMove(mouse) => {
+                let hot = popup::row_at(mouse.pos.y, self.entries.len());
+                if hot != self.hot {
+                    self.hot = hot;
+                    ctx.request_paint();
+                }
+            }
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if let Some(row) = popup::row_at(mouse.pos.y, self.entries.len()) {
+                    let item = self.entries[row].item;
+                    ctx.submit_command(ACTIVATE_ITEM.with(item).to(self.target));
+                    ctx.window().close();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, event, _data, _env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, _env: &Env) {
+        popup::lifecycle(ctx, event);
+    }
+
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        popup::update(ctx, env);
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    #[instrument(
+        name = "OverflowMenu",
+        level = "trace",
+        skip(self, ctx, bc, _data, env)
+    )]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        for entry in self.entries.iter_mut() {
+            entry.label.rebuild_if_needed(ctx.text(), env);
+        }
+        bc.max()
+    }
+
+    #[instrument(name = "OverflowMenu", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        popup::paint_background(ctx, env);
+        for (row, entry) in self.entries.iter().enumerate() {
+            let y = popup::ROW_HEIGHT * row as f64;
+            if self.hot == Some(row) {
+                popup::paint_highlight(ctx, y, env);
+            }
+            if entry.is_on.as_ref().map_or(false, |is_on| is_on(data)) {
+                let mid = y + popup::ROW_HEIGHT / 2.0;
+                let mut check = BezPath::new();
+                check.move_to((6.0, mid));
+                check.line_to((9.0, mid + 3.0));
+                check.line_to((14.0, mid - 4.0));
+                ctx.stroke(check, &env.get(theme::TEXT_COLOR), 1.5);
+            }
+            let text_y = y + (popup::ROW_HEIGHT - entry.label.size().height) / 2.0;
+            entry.label.draw(ctx, (MENU_CHECK_WIDTH, text_y));
+        }
+        popup::paint_border(ctx, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::tests::move_mouse;
+    use crate::widget::SizedBox;
+    use crate::{MouseButtons, MouseEvent, WidgetExt};
+    use test_log::test;
+
+    #[test]
+    fn items_overflow_from_the_end() {
+        let widths = [30.0, 30.0, 9.0, 30.0];
+        assert_eq!(fitting_items(&widths, 20.0, 100.0), 4);
+        assert_eq!(fitting_items(&widths, 20.0, 98.0), 3);
+        assert_eq!(fitting_items(&widths, 20.0, 40.0), 0);
+    }
+
+    #[test]
+    fn clicking_a_toggle_flips_it() {
+        let icon = || SizedBox::empty().width(16.).height(16.);
+        let toolbar = Toolbar::new()
+            .with_button(icon(), "Reset", |_, data: &mut bool, _| *data = false)
+            .with_toggle(icon(), "Enable", crate::lens::Identity);
+
+        Harness::create_simple(false, toolbar, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let click = |harness: &mut Harness<bool>, x| {
+                let mouse = MouseEvent {
+                    button: MouseButton::Left,
+                    buttons: MouseButtons::new().with(MouseButton::Left),
+                    ..move_mouse((x, 10.))
+                };
+                harness.event(Event::MouseMove(mouse.clone()));
+                harness.event(Event::MouseDown(mouse.clone()));
+                harness.event(Event::MouseUp(MouseEvent {
+                    buttons: MouseButtons::new(),
+                    ..mouse
+                }));
+            };
+            // Each button is 24 wide: the icon and its padding.
+            click(harness, 30.);
+            assert!(*harness.data());
+            click(harness, 10.);
+            assert!(!*harness.data());
+        });
+    }
+
+    #[test]
+    fn overflowed_icons_have_no_size() {
+        let [shown, hidden] = widget_ids();
+        let icon = |id| SizedBox::empty().width(16.).height(16.).with_id(id);
+        let toolbar = Toolbar::new()
+            .with_button(icon(shown), "Cut", |_, _: &mut (), _| ())
+            .with_button(icon(hidden), "Copy", |_, _, _| ());
+        let widget = SizedBox::new(toolbar).width(50.).align_left();
+
+        Harness::create_simple((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(
+                harness.get_state(shown).layout_rect().size(),
+                Size::new(16., 16.)
+            );
+            assert_eq!(harness.get_state(hidden).layout_rect().size(), Size::ZERO);
+        });
+    }
+}
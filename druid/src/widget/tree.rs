@@ -0,0 +1,695 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A tree widget with expandable nodes.
+
+use std::collections::{BTreeSet, HashMap};
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::kurbo::BezPath;
+use crate::widget::prelude::*;
+use crate::{theme, InternalLifeCycle, KbKey, MouseButton, Point, Rect, WidgetPod};
+
+/// The default horizontal offset of each level of the tree.
+const DEFAULT_INDENT: f64 = 16.0;
+/// Half the size of the triangle that shows whether a node is expanded.
+const DISCLOSURE_SIZE: f64 = 4.0;
+
+type LabelPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+type NodeCallback<T> = Box<dyn Fn(&mut EventCtx, &mut T, &Env)>;
+
+/// A node of the data shown by a [`Tree`].
+///
+/// The children of a node are of the same type as the node itself.
+pub trait TreeNode: Data {
+    /// The number of children of this node.
+    fn children_count(&self) -> usize;
+
+    /// The child at `index`.
+    ///
+    /// `index` is always less than [`children_count`](TreeNode::children_count).
+    fn get_child(&self, index: usize) -> &Self;
+
+    /// Call `cb` with a mutable reference to the child at `index`, and `index`.
+    fn for_child_mut(&mut self, index: usize, cb: impl FnMut(&mut Self, usize));
+
+    /// Whether this node can be expanded.
+    ///
+    /// Nodes that load their children lazily should return `true` while their
+    /// children are not loaded yet, so that they can be expanded at all.
+    fn is_branch(&self) -> bool {
+        self.children_count() > 0
+    }
+
+    /// An identifier for this node that stays the same when nodes are added to
+    /// or removed from its parent.
+    ///
+    /// The tree remembers which nodes are expanded and selected by this id. It
+    /// only has to be unique among the children of one node.
+    ///
+    /// The default returns `None`, which identifies the node by its index. In
+    /// that case inserting or removing a sibling before an expanded or selected
+    /// node moves its expanded or selected state onto a different node, so
+    /// trees whose nodes can be rearranged should return an id here.
+    fn id(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// One step of the path from the root to a node, by id where the node has one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum NodeKey {
+    Index(usize),
+    Id(u64),
+}
+
+impl NodeKey {
+    fn of<T: TreeNode>(node: &T, index: usize) -> Self {
+        node.id().map_or(NodeKey::Index(index), NodeKey::Id)
+    }
+}
+
+/// A visible node.
+struct TreeRow<T> {
+    /// The indices of the children leading from the root to the node.
+    path: Vec<usize>,
+    /// The node's identity, which is kept across changes to the data.
+    key: Vec<NodeKey>,
+    label: LabelPod<T>,
+    rect: Rect,
+}
+
+/// A tree of nodes that can be expanded and collapsed.
+///
+/// Every node is shown in a row, using a widget built by the closure passed
+/// to [`Tree::new`], indented according to its depth. Branches have a
+/// triangle in front of them that toggles whether their children are shown.
+///
+/// Nodes are found by their path, the indices of the children leading from
+/// the root to the node. Which nodes are expanded and selected is remembered by
+/// [`TreeNode::id`], so that it survives nodes being added and removed as long
+/// as the data provides ids. The root node is shown as well, and starts out
+/// expanded.
+///
+/// Clicking a row selects it. When the tree has focus, the up and down arrow
+/// keys move the selection, the right arrow key expands the selected node and
+/// the left arrow key collapses it or moves to its parent.
+///
+/// To load children lazily, for instance when showing a file system, have
+/// [`TreeNode::is_branch`] return `true` for nodes that have not been loaded
+/// yet, and fill them in from the [`on_expand`](Tree::on_expand) callback.
+pub struct Tree<T> {
+    make_label: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    on_expand: Option<NodeCallback<T>>,
+    on_select: Option<NodeCallback<T>>,
+    indent: f64,
+    expanded: BTreeSet<Vec<NodeKey>>,
+    selected: Option<Vec<NodeKey>>,
+    /// The visible rows, from top to bottom.
+    rows: Vec<TreeRow<T>>,
+}
+
+/// The paths and keys of all nodes that are visible, in display order.
+fn visible_paths<T: TreeNode>(
+    node: &T,
+    path: &mut Vec<usize>,
+    key: &mut Vec<NodeKey>,
+    expanded: &BTreeSet<Vec<NodeKey>>,
+    out: &mut Vec<(Vec<usize>, Vec<NodeKey>)>,
+) {
+    out.push((path.clone(), key.clone()));
+    if expanded.contains(key) {
+        for index in 0..node.children_count() {
+            let child = node.get_child(index);
+            path.push(index);
+            key.push(NodeKey::of(child, index));
+            visible_paths(child, path, key, expanded, out);
+            key.pop();
+            path.pop();
+        }
+    }
+}
+
+/// The node at `path`, if there is one.
+fn node_at<'a, T: TreeNode>(root: &'a T, path: &[usize]) -> Option<&'a T> {
+    path.iter().try_fold(root, |node, &index| {
+        (index < node.children_count()).then(|| node.get_child(index))
+    })
+}
+
+/// Call `f` with the node at `path`, if there is one.
+fn with_node_mut<T: TreeNode>(root: &mut T, path: &[usize], f: &mut dyn FnMut(&mut T)) {
+    match path.split_first() {
+        None => f(root),
+        Some((&first, rest)) if first < root.children_count() => {
+            root.for_child_mut(first, |child, _| with_node_mut(child, rest, &mut *f));
+        }
+        Some(_) => (),
+    }
+}
+
+impl<T: TreeNode> Tree<T> {
+    /// Create a new tree, using `make_label` to build the widget for each node.
+    pub fn new<W: Widget<T> + 'static>(make_label: impl Fn() -> W + 'static) -> Self {
+        let mut expanded = BTreeSet::new();
+        expanded.insert(Vec::new());
+        Tree {
+            make_label: Box::new(move || Box::new(make_label())),
+            on_expand: None,
+            on_select: None,
+            indent: DEFAULT_INDENT,
+            expanded,
+            selected: None,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the horizontal offset of each level.
+    pub fn with_indent(mut self, indent: f64) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Builder-style method to set a callback that is called with a node
+    /// whenever it is expanded.
+    ///
+    /// This is the place to load the children of the node.
+    pub fn on_expand(mut self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.on_expand = Some(Box::new(f));
+        self
+    }
+
+    /// Builder-style method to set a callback that is called with a node
+    /// whenever it is selected.
+    pub fn on_select(mut self, f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    /// The path of the selected node, if any.
+    pub fn selected(&self) -> Option<&[usize]> {
+        self.selected_row().map(|row| &self.rows[row].path[..])
+    }
+
+    /// Returns `true` if the node at `path` is visible and expanded.
+    pub fn is_expanded(&self, path: &[usize]) -> bool {
+        self.rows
+            .iter()
+            .find(|row| row.path == path)
+            .map_or(false, |row| self.expanded.contains(&row.key))
+    }
+
+    /// Rebuild the list of visible rows, reusing the widgets of rows that
+    /// stay visible.
+    ///
+    /// Returns `true` if any widgets were added or removed.
+    fn sync_rows(&mut self, data: &T) -> bool {
+        let mut paths = Vec::new();
+        visible_paths(
+            data,
+            &mut Vec::new(),
+            &mut Vec::new(),
+            &self.expanded,
+            &mut paths,
+        );
+
+        let mut old: HashMap<_, _> = self
+            .rows
+            .drain(..)
+            .map(|row| (row.key, (row.label, row.rect)))
+            .collect();
+        let mut added = false;
+        let make_label = &self.make_label;
+        let rows = paths
+            .into_iter()
+            .map(|(path, key)| {
+                let (label, rect) = old.remove(&key).unwrap_or_else(|| {
+                    added = true;
+                    (WidgetPod::new(make_label()), Rect::ZERO)
+                });
+                TreeRow {
+                    path,
+                    key,
+                    label,
+                    rect,
+                }
+            })
+            .collect();
+        self.rows = rows;
+
+        if self.selected_row().is_none() {
+            self.selected = None;
+        }
+        added || !old.is_empty()
+    }
+
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        self.rows.iter().position(|row| row.rect.contains(pos))
+    }
+
+    fn selected_row(&self) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        self.rows.iter().position(|row| &row.key == selected)
+    }
+
+    fn row_is_expanded(&self, row: usize) -> bool {
+        self.expanded.contains(&self.rows[row].key)
+    }
+
+    fn select(&mut self, ctx: &mut EventCtx, row: usize, data: &mut T, env: &Env) {
+        if let Some(on_select) = &self.on_select {
+            with_node_mut(data, &self.rows[row].path, &mut |node| {
+                on_select(ctx, node, env)
+            });
+        }
+        ctx.scroll_area_to_view(self.rows[row].rect);
+        self.selected = Some(self.rows[row].key.clone());
+        ctx.request_paint();
+    }
+
+    fn set_expanded(
+        &mut self,
+        ctx: &mut EventCtx,
+        row: usize,
+        expand: bool,
+        data: &mut T,
+        env: &Env,
+    ) {
+        let key = self.rows[row].key.clone();
+        if expand {
+            self.expanded.insert(key);
+            if let Some(on_expand) = &self.on_expand {
+                with_node_mut(data, &self.rows[row].path, &mut |node| {
+                    on_expand(ctx, node, env)
+                });
+            }
+        } else {
+            // Don't leave the selection on a hidden node.
+            if let Some(selected) = &self.selected {
+                if selected.len() > key.len() && selected.starts_with(&key) {
+                    self.selected = Some(key.clone());
+                }
+            }
+            self.expanded.remove(&key);
+        }
+
+        if self.sync_rows(data) {
+            ctx.children_changed();
+        }
+        ctx.request_layout();
+    }
+
+    fn handle_key(&mut self, ctx: &mut EventCtx, key: &KbKey, data: &mut T, env: &Env) -> bool {
+        let current = self.selected_row();
+        match (key, current) {
+            (KbKey::ArrowDown, None) | (KbKey::ArrowUp, None) if !self.rows.is_empty() => {
+                self.select(ctx, 0, data, env);
+            }
+            (KbKey::ArrowDown, Some(row)) if row + 1 < self.rows.len() => {
+                self.select(ctx, row + 1, data, env);
+            }
+            (KbKey::ArrowUp, Some(row)) if row > 0 => {
+                self.select(ctx, row - 1, data, env);
+            }
+            (KbKey::ArrowRight, Some(row)) => {
+                let path = &self.rows[row].path;
+                if !node_at(data, path).map_or(false, TreeNode::is_branch) {
+                    return false;
+                }
+                let has_visible_children = self
+                    .rows
+                    .get(row + 1)
+                    .map_or(false, |next| next.path.len() > path.len());
+                if !self.row_is_expanded(row) {
+                    self.set_expanded(ctx, row, true, data, env);
+                } else if has_visible_children {
+                    self.select(ctx, row + 1, data, env);
+                }
+            }
+            (KbKey::ArrowLeft, Some(row)) => {
+                let path = self.rows[row].path.clone();
+                if self.row_is_expanded(row) {
+                    self.set_expanded(ctx, row, false, data, env);
+                } else if let Some((_, parent)) = path.split_last() {
+                    if let Some(parent_row) = self.rows.iter().position(|row| row.path == parent) {
+                        self.select(ctx, parent_row, data, env);
+                    }
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<T: TreeNode> Widget<T> for Tree<T> {
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button == MouseButton::Left => {
+                if let Some(row) = self.row_at(mouse.pos) {
+                    ctx.request_focus();
+                    let path = &self.rows[row].path;
+                    let on_disclosure = mouse.pos.x < self.rows[row].label.layout_rect().x0;
+                    if on_disclosure && node_at(data, path).map_or(false, TreeNode::is_branch) {
+                        let expand = !self.row_is_expanded(row);
+                        self.set_expanded(ctx, row, expand, data, env);
+                    } else {
+                        self.select(ctx, row, data, env);
+                    }
+                }
+            }
+            Event::KeyDown(key) if ctx.is_focused() => {
+                if self.handle_key(ctx, &key.key, data, env) {
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            _ => (),
+        }
+
+        for row in self
+            .rows
+            .iter_mut()
+            .filter(|row| row.label.is_initialized())
+        {
+            let label = &mut row.label;
+            with_node_mut(data, &row.path, &mut |node| {
+                label.event(ctx, event, node, env)
+            });
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.sync_rows(data);
+            }
+            LifeCycle::BuildFocusChain => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+
+        // Rows that were just shown only get to see `WidgetAdded`.
+        let adding = matches!(
+            event,
+            LifeCycle::WidgetAdded | LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded)
+        );
+        for row in &mut self.rows {
+            if adding || row.label.is_initialized() {
+                if let Some(node) = node_at(data, &row.path) {
+                    row.label.lifecycle(ctx, event, node, env);
+                }
+            }
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        // Update the existing rows before showing new ones, which must get
+        // `WidgetAdded` first.
+        for row in self
+            .rows
+            .iter_mut()
+            .filter(|row| row.label.is_initialized())
+        {
+            if let Some(node) = node_at(data, &row.path) {
+                row.label.update(ctx, node, env);
+            }
+        }
+
+        if !old_data.same(data) {
+            if self.sync_rows(data) {
+                ctx.children_changed();
+            }
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Tree");
+
+        let max_width = bc.max().width;
+        let mut content_width: f64 = 0.0;
+        let mut y = 0.0;
+        for row in &mut self.rows {
+            let node = match node_at(data, &row.path) {
+                Some(node) if row.label.is_initialized() => node,
+                _ => continue,
+            };
+            let x = self.indent * (row.path.len() + 1) as f64;
+            let label_bc = BoxConstraints::new(
+                Size::ZERO,
+                Size::new((max_width - x).max(0.0), f64::INFINITY),
+            );
+            let label_size = row.label.layout(ctx, &label_bc, node, env);
+            let height = label_size.height.max(self.indent);
+            row.label
+                .set_origin(ctx, Point::new(x, y + (height - label_size.height) / 2.0));
+            row.rect = Rect::new(0.0, y, 0.0, y + height);
+            content_width = content_width.max(x + label_size.width);
+            y += height;
+        }
+
+        let size = bc.constrain(Size::new(content_width, y));
+        for row in &mut self.rows {
+            row.rect.x1 = size.width;
+        }
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "Tree", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let selection_color = if ctx.is_focused() {
+            env.get(theme::SELECTED_TEXT_BACKGROUND_COLOR)
+        } else {
+            env.get(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR)
+        };
+        let arrow_color = env.get(theme::TEXT_COLOR);
+
+        for row in &mut self.rows {
+            // Rows shown since the last layout are painted after the next one.
+            if !row.label.is_initialized() || row.label.layout_requested() {
+                continue;
+            }
+            let node = match node_at(data, &row.path) {
+                Some(node) => node,
+                None => continue,
+            };
+
+            if self.selected.as_ref() == Some(&row.key) {
+                ctx.fill(row.rect, &selection_color);
+            }
+
+            if node.is_branch() {
+                let center = Point::new(
+                    self.indent * (row.path.len() as f64 + 0.5),
+                    row.rect.center().y,
+                );
+                let d = DISCLOSURE_SIZE;
+                let mut arrow = BezPath::new();
+                if self.expanded.contains(&row.key) {
+                    arrow.move_to((center.x - d, center.y - d / 2.0));
+                    arrow.line_to((center.x + d, center.y - d / 2.0));
+                    arrow.line_to((center.x, center.y + d / 2.0));
+                } else {
+                    arrow.move_to((center.x - d / 2.0, center.y - d));
+                    arrow.line_to((center.x + d / 2.0, center.y));
+                    arrow.line_to((center.x - d / 2.0, center.y + d));
+                }
+                arrow.close_path();
+                ctx.fill(arrow, &arrow_color);
+            }
+
+            row.label.paint(ctx, node, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let children = self
+            .rows
+            .iter()
+            .filter_map(|row| Some(row.label.widget().debug_state(node_at(data, &row.path)?)))
+            .collect();
+
+        DebugState {
+            display_name: "Tree".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::move_mouse;
+    use crate::widget::{Label, SizedBox};
+    use crate::{KeyEvent, Modifiers, MouseEvent};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[derive(Clone, Data, Debug)]
+    struct Node {
+        name: &'static str,
+        id: Option<u64>,
+        loaded: bool,
+        children: Arc<Vec<Node>>,
+    }
+
+    impl Node {
+        fn new(name: &'static str, children: Vec<Node>) -> Self {
+            Node {
+                name,
+                id: None,
+                loaded: true,
+                children: Arc::new(children),
+            }
+        }
+
+        fn with_id(self, id: u64) -> Self {
+            Node {
+                id: Some(id),
+                ..self
+            }
+        }
+
+        fn unloaded(name: &'static str) -> Self {
+            Node {
+                loaded: false,
+                ..Node::new(name, Vec::new())
+            }
+        }
+    }
+
+    impl TreeNode for Node {
+        fn children_count(&self) -> usize {
+            self.children.len()
+        }
+
+        fn get_child(&self, index: usize) -> &Self {
+            &self.children[index]
+        }
+
+        fn for_child_mut(&mut self, index: usize, mut cb: impl FnMut(&mut Self, usize)) {
+            let mut child = self.children[index].clone();
+            cb(&mut child, index);
+            if !child.same(&self.children[index]) {
+                Arc::make_mut(&mut self.children)[index] = child;
+            }
+        }
+
+        fn is_branch(&self) -> bool {
+            !self.loaded || !self.children.is_empty()
+        }
+
+        fn id(&self) -> Option<u64> {
+            self.id
+        }
+    }
+
+    fn paths(root: &Node, expanded: &BTreeSet<Vec<NodeKey>>) -> Vec<Vec<usize>> {
+        let mut out = Vec::new();
+        visible_paths(root, &mut Vec::new(), &mut Vec::new(), expanded, &mut out);
+        out.into_iter().map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn visible_paths_follow_expansion() {
+        let a = Node::new("a", vec![Node::new("a0", vec![]), Node::new("a1", vec![])]);
+        let root = Node::new("root", vec![a, Node::new("b", vec![])]);
+        let mut expanded = BTreeSet::new();
+        expanded.insert(vec![]);
+        assert_eq!(paths(&root, &expanded), vec![vec![], vec![0], vec![1]]);
+
+        expanded.insert(vec![NodeKey::Index(0)]);
+        assert_eq!(
+            paths(&root, &expanded),
+            vec![vec![], vec![0], vec![0, 0], vec![0, 1], vec![1]]
+        );
+
+        assert_eq!(node_at(&root, &[0, 1]).map(|node| node.name), Some("a1"));
+        assert!(node_at(&root, &[1, 0]).is_none());
+    }
+
+    #[test]
+    fn expansion_follows_node_ids() {
+        let a = Node::new("a", vec![Node::new("a0", vec![])]).with_id(1);
+        let root = Node::new("root", vec![a.clone(), Node::new("b", vec![]).with_id(2)]);
+        let mut expanded = BTreeSet::new();
+        expanded.insert(vec![]);
+        expanded.insert(vec![NodeKey::Id(1)]);
+        assert_eq!(
+            paths(&root, &expanded),
+            vec![vec![], vec![0], vec![0, 0], vec![1]]
+        );
+
+        // Inserting a sibling before `a` keeps `a` expanded, at its new index.
+        let root = Node::new("root", vec![Node::new("new", vec![]).with_id(3), a]);
+        assert_eq!(
+            paths(&root, &expanded),
+            vec![vec![], vec![0], vec![1], vec![1, 0]]
+        );
+    }
+
+    #[test]
+    fn lazy_loading_and_keyboard_navigation() {
+        let root = Node::new("root", vec![Node::unloaded("a")]);
+        let selected = Rc::new(RefCell::new(Vec::new()));
+        let selected_clone = selected.clone();
+        let tree = Tree::new(|| SizedBox::new(Label::new("node")).height(20.0))
+            .on_expand(|_, node: &mut Node, _| {
+                if !node.loaded {
+                    *node = Node::new(node.name, vec![Node::new("a0", vec![])]);
+                }
+            })
+            .on_select(move |_, node, _| selected_clone.borrow_mut().push(node.name));
+
+        Harness::create_simple(root, tree, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+
+            // Click the disclosure triangle of the unloaded node, in the second row.
+            let click = MouseEvent {
+                button: MouseButton::Left,
+                ..move_mouse((DEFAULT_INDENT * 1.5, 30.0))
+            };
+            harness.event(Event::MouseDown(click));
+            assert_eq!(harness.data().children[0].children.len(), 1);
+            harness.just_layout();
+
+            // The tree took focus, so the arrow keys move the selection.
+            for key in [
+                KbKey::ArrowDown,
+                KbKey::ArrowDown,
+                KbKey::ArrowRight,
+                KbKey::ArrowLeft,
+            ] {
+                harness.event(Event::KeyDown(KeyEvent::for_test(
+                    Modifiers::default(),
+                    key,
+                )));
+            }
+            assert_eq!(*selected.borrow(), vec!["root", "a", "a0", "a"]);
+        });
+    }
+}
@@ -23,7 +23,8 @@ use crate::widget::prelude::*;
 use crate::{Data, Selector};
 
 const BEGIN_EDITING: Selector = Selector::new("druid.builtin.textbox-begin-editing");
-const COMPLETE_EDITING: Selector = Selector::new("druid.builtin.textbox-complete-editing");
+pub(crate) const COMPLETE_EDITING: Selector =
+    Selector::new("druid.builtin.textbox-complete-editing");
 
 /// A `TextBox` that uses a [`Formatter`] to handle formatting and validation
 /// of its data.
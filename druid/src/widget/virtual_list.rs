@@ -0,0 +1,344 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A list widget that only has widgets for the visible items.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use tracing::{instrument, trace};
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::widget::ListIter;
+use crate::{InternalLifeCycle, Point, Rect, WidgetPod};
+
+/// The default height of each row.
+const DEFAULT_ROW_HEIGHT: f64 = 24.0;
+/// Rows kept alive beyond each end of the visible area, so that small scrolls
+/// don't need new widgets.
+const OVERSCAN_ROWS: usize = 4;
+
+type RowPod<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+
+/// A vertical list that only creates widgets for the items that are visible.
+///
+/// Unlike [`List`], which builds a widget for every item of the collection,
+/// `VirtualList` asks its enclosing [`Scroll`] which part of it is visible and
+/// only keeps widgets for the rows in and around that area. Widgets of rows
+/// that scroll out of view are reused for the rows that scroll into view, so
+/// scrolling does not build new widgets either.
+///
+/// All rows have the same height, set with [`with_row_height`]. A reused row
+/// widget is added to the tree again, with a new id, and gets
+/// [`LifeCycle::WidgetAdded`] with the data of its new item; it should not keep
+/// other state of its own that belongs to a particular item.
+///
+/// [`List`]: super::List
+/// [`Scroll`]: super::Scroll
+/// [`with_row_height`]: VirtualList::with_row_height
+pub struct VirtualList<T> {
+    closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
+    row_height: f64,
+    /// The widgets of the rows near the visible area, by index.
+    rows: BTreeMap<usize, RowPod<T>>,
+    /// Widgets of rows that went out of view, to be reused.
+    pool: Vec<Box<dyn Widget<T>>>,
+    len: usize,
+    clip: Option<Rect>,
+}
+
+impl<T: Data> VirtualList<T> {
+    /// Create a new virtual list. The closure is called whenever a new row
+    /// widget is needed.
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        VirtualList {
+            closure: Box::new(move || Box::new(closure())),
+            row_height: DEFAULT_ROW_HEIGHT,
+            rows: BTreeMap::new(),
+            pool: Vec::new(),
+            len: 0,
+            clip: None,
+        }
+    }
+
+    /// Builder-style method to set the height of each row.
+    pub fn with_row_height(mut self, height: f64) -> Self {
+        self.row_height = height;
+        self
+    }
+
+    /// The indices of the rows that should have widgets.
+    fn visible_rows(&self) -> Range<usize> {
+        let clip = match self.clip {
+            Some(clip) => clip,
+            None => return 0..0,
+        };
+        let first = (clip.y0 / self.row_height).floor().max(0.0) as usize;
+        let last = (clip.y1 / self.row_height).ceil().max(0.0) as usize;
+        first.saturating_sub(OVERSCAN_ROWS).min(self.len)..(last + OVERSCAN_ROWS).min(self.len)
+    }
+
+    /// Move widgets between rows and the pool, so that only the rows near the
+    /// visible area have them.
+    ///
+    /// Returns whether any row got or lost a widget, in which case the children
+    /// have changed.
+    fn realize_rows(&mut self) -> bool {
+        let visible = self.visible_rows();
+        let hidden: Vec<usize> = self
+            .rows
+            .keys()
+            .copied()
+            .filter(|idx| !visible.contains(idx))
+            .collect();
+        let mut changed = !hidden.is_empty();
+        for idx in hidden {
+            // Dropping the pod drops the hot, active and focus state of the old item.
+            self.pool
+                .extend(self.rows.remove(&idx).map(WidgetPod::into_inner));
+        }
+
+        for idx in visible {
+            if self.rows.contains_key(&idx) {
+                continue;
+            }
+            let widget = self.pool.pop().unwrap_or_else(|| (self.closure)());
+            self.rows.insert(idx, WidgetPod::new(widget));
+            changed = true;
+        }
+        changed
+    }
+}
+
+impl<C: Data, T: ListIter<C>> Widget<T> for VirtualList<C> {
+    #[instrument(
+        name = "VirtualList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for (&idx, row) in self.rows.iter_mut().filter(|(_, row)| row.is_initialized()) {
+            data.for_index_mut(idx, |child_data| row.event(ctx, event, child_data, env));
+        }
+    }
+
+    #[instrument(
+        name = "VirtualList",
+        level = "trace",
+        skip(self, ctx, event, data, env)
+    )]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.len = data.data_len(),
+            LifeCycle::ViewContextChanged(view_context) => {
+                self.clip = Some(view_context.clip);
+                if self.realize_rows() {
+                    // New and reused rows get `WidgetAdded` with their item's data.
+                    ctx.children_changed();
+                }
+            }
+            _ => (),
+        }
+
+        // Rows that were just created only get to see `WidgetAdded`.
+        let adding = matches!(
+            event,
+            LifeCycle::WidgetAdded | LifeCycle::Internal(InternalLifeCycle::RouteWidgetAdded)
+        );
+        for (&idx, row) in &mut self.rows {
+            if adding || row.is_initialized() {
+                data.for_index(idx, |child_data| row.lifecycle(ctx, event, child_data, env));
+            }
+        }
+    }
+
+    #[instrument(
+        name = "VirtualList",
+        level = "trace",
+        skip(self, ctx, _old_data, data, env)
+    )]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        if data.data_len() != self.len {
+            self.len = data.data_len();
+            if self.realize_rows() {
+                ctx.children_changed();
+            }
+            ctx.request_layout();
+        }
+
+        // Rows that were just created are updated after they got `WidgetAdded`.
+        for (&idx, row) in self.rows.iter_mut().filter(|(_, row)| row.is_initialized()) {
+            data.for_index(idx, |child_data| row.update(ctx, child_data, env));
+        }
+    }
+
+    #[instrument(name = "VirtualList", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("VirtualList");
+
+        let row_height = self.row_height;
+        let row_bc = BoxConstraints::new(
+            Size::new(0.0, row_height),
+            Size::new(bc.max().width, row_height),
+        );
+        let mut width = bc.min().width;
+        for (&idx, row) in self.rows.iter_mut().filter(|(_, row)| row.is_initialized()) {
+            data.for_index(idx, |child_data| {
+                let size = row.layout(ctx, &row_bc, child_data, env);
+                row.set_origin(ctx, Point::new(0.0, row_height * idx as f64));
+                width = width.max(size.width);
+            });
+        }
+
+        if bc.is_width_bounded() {
+            width = bc.max().width;
+        }
+        let size = bc.constrain(Size::new(width, row_height * self.len as f64));
+        trace!("Computed size: {}", size);
+        size
+    }
+
+    #[instrument(name = "VirtualList", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        // Rows created or reused since the last layout are painted after the next one.
+        for (&idx, row) in self
+            .rows
+            .iter_mut()
+            .filter(|(_, row)| row.is_initialized() && !row.layout_requested())
+        {
+            data.for_index(idx, |child_data| row.paint(ctx, child_data, env));
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        let mut children = Vec::new();
+        for (&idx, row) in &self.rows {
+            data.for_index(idx, |child_data| {
+                children.push(row.widget().debug_state(child_data))
+            });
+        }
+
+        DebugState {
+            display_name: "VirtualList".to_string(),
+            children,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::ModularWidget;
+    use crate::tests::{move_mouse, scroll_mouse};
+    use crate::widget::{Label, Scroll, SizedBox};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+    use test_log::test;
+
+    #[test]
+    fn rows_are_recycled_on_scroll() {
+        let created = Rc::new(Cell::new(0));
+        let created_clone = created.clone();
+        let list = VirtualList::new(move || {
+            created_clone.set(created_clone.get() + 1);
+            Label::dynamic(|n: &u32, _| n.to_string())
+        });
+        let data = Arc::new((0..10_000).collect::<Vec<u32>>());
+        let widget = SizedBox::new(Scroll::new(list).vertical()).height(240.0);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert!(created.get() > 0);
+            assert!(created.get() < 30);
+
+            // Once the overscan above the first row is filled, scrolling further
+            // only reuses existing widgets.
+            harness.event(Event::Wheel(scroll_mouse((10., 10.), (0., 1000.))));
+            harness.just_layout();
+            let after_first_scroll = created.get();
+            harness.event(Event::Wheel(scroll_mouse((10., 10.), (0., 1000.))));
+            harness.just_layout();
+            assert_eq!(created.get(), after_first_scroll);
+        });
+    }
+
+    #[test]
+    fn reused_rows_are_added_with_their_new_item() {
+        let list = VirtualList::new(|| {
+            ModularWidget::new(None)
+                .lifecycle_fn(|added, _, event, data: &u32, _| {
+                    if let LifeCycle::WidgetAdded = event {
+                        *added = Some(*data);
+                    }
+                })
+                .layout_fn(|added, _, bc, data, _| {
+                    assert_eq!(*added, Some(*data));
+                    bc.max()
+                })
+        });
+        let data = Arc::new((0..1_000).collect::<Vec<u32>>());
+        let widget = SizedBox::new(Scroll::new(list).vertical()).height(240.0);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            for _ in 0..3 {
+                harness.event(Event::Wheel(scroll_mouse((10., 10.), (0., 1000.))));
+                harness.just_layout();
+            }
+        });
+    }
+
+    /// An item that counts how often it is cloned.
+    struct CountedItem(Rc<Cell<usize>>);
+
+    impl Clone for CountedItem {
+        fn clone(&self) -> Self {
+            self.0.set(self.0.get() + 1);
+            CountedItem(self.0.clone())
+        }
+    }
+
+    impl Data for CountedItem {
+        fn same(&self, _other: &Self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn events_only_clone_realized_items() {
+        let clones = Rc::new(Cell::new(0));
+        let list = VirtualList::new(SizedBox::empty);
+        let data = Arc::new(
+            (0..10_000)
+                .map(|_| CountedItem(clones.clone()))
+                .collect::<Vec<_>>(),
+        );
+        let widget = SizedBox::new(Scroll::new(list).vertical()).height(240.0);
+
+        Harness::create_simple(data, widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            clones.set(0);
+            harness.event(Event::MouseMove(move_mouse((10., 100.))));
+            assert!(clones.get() > 0);
+            assert!(clones.get() < 30);
+        });
+    }
+}
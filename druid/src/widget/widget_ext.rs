@@ -18,12 +18,12 @@ use super::invalidation::DebugInvalidation;
 #[allow(deprecated)]
 use super::Parse;
 use super::{
-    Added, Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, LensWrap, Padding, SizedBox, WidgetId,
+    Added, Align, BackgroundBrush, Click, Container, ContextClick, Controller, ControllerHost,
+    EnvScope, IdentityWrapper, LensWrap, Padding, SizedBox, WidgetId,
 };
 use crate::widget::{DisabledIf, Scroll};
 use crate::{
-    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, UnitPoint, Widget,
+    Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LifeCycleCtx, Point, UnitPoint, Widget,
 };
 
 /// A trait that provides extra methods for combining `Widget`s.
@@ -178,6 +178,28 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
+    /// Control the events of this widget with a [`Click`] widget that is
+    /// clicked with the middle mouse button. See [`Click::middle`].
+    fn on_middle_click(
+        self,
+        f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, Click<T>> {
+        ControllerHost::new(self, Click::middle(f))
+    }
+
+    /// Control the events of this widget with a [`ContextClick`] widget. The
+    /// closure provided will be called, with the position to show a menu at,
+    /// when the user asks for a context menu in the way that is usual on the
+    /// current platform.
+    ///
+    /// This is typically used to show a menu with [`EventCtx::show_context_menu`].
+    fn on_context_click(
+        self,
+        f: impl Fn(&mut EventCtx, &mut T, &Env, Point) + 'static,
+    ) -> ControllerHost<Self, ContextClick<T>> {
+        ControllerHost::new(self, ContextClick::new(f))
+    }
+
     /// Draw the [`layout`] `Rect`s of  this widget and its children.
     ///
     /// [`layout`]: Widget::layout
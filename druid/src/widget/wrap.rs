@@ -0,0 +1,221 @@
+// Copyright 2023 The Druid Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that lays out its children in lines, wrapping when out of width.
+
+use tracing::instrument;
+
+use crate::debug_state::DebugState;
+use crate::widget::prelude::*;
+use crate::{theme, KeyOrValue, Point, Rect, WidgetPod};
+
+/// A container that lays out its children left-to-right, starting a new line
+/// whenever the next child doesn't fit in the available width.
+///
+/// This is useful for tag clouds and groups of buttons. Children keep their
+/// own size and are aligned to the top of their line.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Wrap};
+///
+/// let buttons = Wrap::new()
+///     .with_item_spacing(4.0)
+///     .with_line_spacing(8.0)
+///     .with_child(Button::<()>::new("Cut"))
+///     .with_child(Button::new("Copy"))
+///     .with_child(Button::new("Paste"));
+/// ```
+pub struct Wrap<T> {
+    children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    item_spacing: KeyOrValue<f64>,
+    line_spacing: KeyOrValue<f64>,
+}
+
+impl<T: Data> Wrap<T> {
+    /// Create a new, empty `Wrap`.
+    ///
+    /// The spacing between items and lines defaults to the theme's widget padding.
+    pub fn new() -> Self {
+        Wrap {
+            children: Vec::new(),
+            item_spacing: theme::WIDGET_PADDING_HORIZONTAL.into(),
+            line_spacing: theme::WIDGET_PADDING_VERTICAL.into(),
+        }
+    }
+
+    /// Builder-style variant of `add_child`.
+    pub fn with_child(mut self, child: impl Widget<T> + 'static) -> Self {
+        self.add_child(child);
+        self
+    }
+
+    /// Builder-style method for setting the horizontal space between items on a line.
+    pub fn with_item_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_item_spacing(spacing);
+        self
+    }
+
+    /// Builder-style method for setting the vertical space between lines.
+    pub fn with_line_spacing(mut self, spacing: impl Into<KeyOrValue<f64>>) -> Self {
+        self.set_line_spacing(spacing);
+        self
+    }
+
+    /// Add a child widget.
+    ///
+    /// See also [`with_child`].
+    ///
+    /// [`with_child`]: Wrap::with_child
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static) {
+        self.children.push(WidgetPod::new(Box::new(child)));
+    }
+
+    /// Set the horizontal space between items on a line.
+    pub fn set_item_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.item_spacing = spacing.into();
+    }
+
+    /// Set the vertical space between lines.
+    pub fn set_line_spacing(&mut self, spacing: impl Into<KeyOrValue<f64>>) {
+        self.line_spacing = spacing.into();
+    }
+}
+
+impl<T: Data> Default for Wrap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Wrap<T> {
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for child in &mut self.children {
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, event, data, env))]
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, _old_data, data, env))]
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.update(ctx, data, env);
+        }
+        if ctx.env_key_changed(&self.item_spacing) || ctx.env_key_changed(&self.line_spacing) {
+            ctx.request_layout();
+        }
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, bc, data, env))]
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Wrap");
+        let item_spacing = self.item_spacing.resolve(env);
+        let line_spacing = self.line_spacing.resolve(env);
+        let max_width = bc.max().width;
+        let child_bc = BoxConstraints::new(Size::ZERO, Size::new(max_width, f64::INFINITY));
+
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut line_height: f64 = 0.0;
+        let mut width: f64 = 0.0;
+        let mut paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let size = child.layout(ctx, &child_bc, data, env);
+            if x > 0.0 && x + size.width > max_width {
+                x = 0.0;
+                y += line_height + line_spacing;
+                line_height = 0.0;
+            }
+            child.set_origin(ctx, Point::new(x, y));
+            paint_rect = paint_rect.union(child.paint_rect());
+            width = width.max(x + size.width);
+            line_height = line_height.max(size.height);
+            x += size.width + item_spacing;
+        }
+
+        let my_size = bc.constrain(Size::new(width, y + line_height).expand());
+        let my_bounds = Rect::ZERO.with_size(my_size);
+        let insets = paint_rect - my_bounds;
+        ctx.set_paint_insets(insets);
+        my_size
+    }
+
+    #[instrument(name = "Wrap", level = "trace", skip(self, ctx, data, env))]
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.paint(ctx, data, env);
+        }
+    }
+
+    fn debug_state(&self, data: &T) -> DebugState {
+        DebugState {
+            display_name: self.short_type_name().to_string(),
+            children: self
+                .children
+                .iter()
+                .map(|child| child.widget().debug_state(data))
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::harness::Harness;
+    use crate::tests::helpers::widget_ids;
+    use crate::widget::SizedBox;
+    use crate::WidgetExt;
+    use test_log::test;
+
+    #[test]
+    fn wraps_to_new_line() {
+        let [id1, id2, id3, id_wrap] = widget_ids();
+        let wrap = Wrap::new()
+            .with_item_spacing(10.0)
+            .with_line_spacing(5.0)
+            .with_child(SizedBox::empty().fix_size(150.0, 20.0).with_id(id1))
+            .with_child(SizedBox::empty().fix_size(150.0, 30.0).with_id(id2))
+            .with_child(SizedBox::empty().fix_size(150.0, 20.0).with_id(id3))
+            .with_id(id_wrap)
+            .align_left();
+
+        Harness::create_simple((), wrap, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(harness.get_state(id1).layout_rect().origin(), Point::ZERO);
+            assert_eq!(
+                harness.get_state(id2).layout_rect().origin(),
+                Point::new(160.0, 0.0)
+            );
+            assert_eq!(
+                harness.get_state(id3).layout_rect().origin(),
+                Point::new(0.0, 35.0)
+            );
+            assert_eq!(
+                harness.get_state(id_wrap).layout_rect().size(),
+                Size::new(310.0, 55.0)
+            );
+        });
+    }
+}
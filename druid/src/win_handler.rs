@@ -535,6 +535,7 @@ impl<T: Data> InnerAppState<T> {
 
     fn window_got_focus(&mut self, window_id: WindowId) {
         if let Some(win) = self.windows.get_mut(window_id) {
+            win.set_active(true);
             if win.menu.is_some() {
                 self.menu_window = Some(window_id);
             }
@@ -543,6 +544,12 @@ impl<T: Data> InnerAppState<T> {
             win.macos_update_app_menu(&self.data, &self.env)
         }
     }
+
+    fn window_lost_focus(&mut self, window_id: WindowId) {
+        if let Some(win) = self.windows.get_mut(window_id) {
+            win.set_active(false);
+        }
+    }
 }
 
 impl<T: Data> DruidHandler<T> {
@@ -578,7 +585,14 @@ impl<T: Data> AppState<T> {
     }
 
     fn window_got_focus(&mut self, window_id: WindowId) {
-        self.inner.borrow_mut().window_got_focus(window_id)
+        self.inner.borrow_mut().window_got_focus(window_id);
+        self.inner.borrow_mut().do_update();
+    }
+
+    fn window_lost_focus(&mut self, window_id: WindowId) {
+        self.inner.borrow_mut().window_lost_focus(window_id);
+        // The pointer no longer interacts with an inactive window, so clear hot state.
+        self.do_window_event(Event::Internal(InternalEvent::MouseLeave), window_id);
     }
 
     /// Send an event to the widget hierarchy.
@@ -1033,6 +1047,10 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         self.app_state.window_got_focus(self.window_id);
     }
 
+    fn lost_focus(&mut self) {
+        self.app_state.window_lost_focus(self.window_id);
+    }
+
     fn timer(&mut self, token: TimerToken) {
         self.app_state
             .do_window_event(Event::Timer(token), self.window_id);
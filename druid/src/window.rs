@@ -16,6 +16,7 @@
 
 use std::collections::{HashMap, VecDeque};
 use std::mem;
+use std::time::Duration;
 use tracing::{error, info, trace_span};
 
 // Automatically defaults to std::time::Instant on non Wasm platforms
@@ -30,6 +31,7 @@ use crate::core::{CommandQueue, FocusChange, WidgetState};
 use crate::debug_state::DebugState;
 use crate::menu::{MenuItemId, MenuManager};
 use crate::text::TextFieldRegistration;
+use crate::theme;
 use crate::widget::LabelText;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
 use crate::{
@@ -40,6 +42,10 @@ use crate::{
 
 pub type ImeUpdateFn = dyn FnOnce(crate::shell::text::Event);
 
+/// How long painting a frame may take before widgets are asked to paint
+/// more cheaply.
+const FRAME_BUDGET: Duration = Duration::from_millis(16);
+
 /// A unique identifier for a window.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct WindowId(u64);
@@ -60,13 +66,98 @@ pub struct Window<T> {
     pub(crate) focus: Option<WidgetId>,
     pub(crate) handle: WindowHandle,
     pub(crate) timers: HashMap<TimerToken, WidgetId>,
+    /// Set by the test harness, which drives timers from a simulated clock.
+    pub(crate) simulated_timers: Option<SimulatedTimers>,
     pub(crate) pending_text_registrations: Vec<TextFieldRegistration>,
     pub(crate) transparent: bool,
+    /// Whether this is the active window, as reported by the platform.
+    active: bool,
+    /// Whether painting the last frame took longer than [`FRAME_BUDGET`].
+    over_budget: bool,
+    /// The app env with the values specific to this window added, along with
+    /// the app env it was derived from.
+    window_env: Option<(Env, Env)>,
     pub(crate) ime_handlers: Vec<(TextFieldToken, TextFieldRegistration)>,
     ext_handle: ExtEventSink,
     pub(crate) ime_focus_change: Option<Option<TextFieldToken>>,
 }
 
+/// The time available for painting a frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PaintBudget {
+    /// The moment painting started, which the paint clock counts from.
+    pub(crate) epoch: Instant,
+    /// When painting should be done, on the paint clock.
+    pub(crate) deadline: Duration,
+    /// Whether the previous frame ran over its budget.
+    pub(crate) over_budget: bool,
+}
+
+impl PaintBudget {
+    /// The current time on the paint clock.
+    ///
+    /// This is the simulated clock when there is one, so that tests don't
+    /// depend on how fast they run.
+    pub(crate) fn now(&self, simulated: Option<&SimulatedTimers>) -> Duration {
+        match simulated {
+            Some(timers) => timers.now,
+            None => self.epoch.elapsed(),
+        }
+    }
+}
+
+/// Timers scheduled against a simulated clock rather than the platform.
+///
+/// This is used by the test harness so that timers fire deterministically.
+#[derive(Debug)]
+pub(crate) struct SimulatedTimers {
+    /// The moment the simulated clock started at.
+    epoch: Instant,
+    /// Time elapsed on the simulated clock.
+    pub(crate) now: Duration,
+    pending: Vec<(Duration, TimerToken)>,
+}
+
+impl Default for SimulatedTimers {
+    fn default() -> Self {
+        SimulatedTimers {
+            epoch: Instant::now(),
+            now: Duration::ZERO,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl SimulatedTimers {
+    /// The current time on the simulated clock, as an [`Instant`].
+    pub(crate) fn instant(&self) -> Instant {
+        self.epoch + self.now
+    }
+
+    pub(crate) fn request_timer(&mut self, deadline: Duration) -> TimerToken {
+        let token = TimerToken::next();
+        self.pending.push((self.now + deadline, token));
+        token
+    }
+
+    /// The deadline of the earliest pending timer.
+    pub(crate) fn next_deadline(&self) -> Option<Duration> {
+        self.pending.iter().map(|(deadline, _)| *deadline).min()
+    }
+
+    /// Remove the earliest pending timer, advancing the clock to its deadline.
+    pub(crate) fn pop_next(&mut self) -> Option<TimerToken> {
+        let (idx, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (deadline, _))| *deadline)?;
+        let (deadline, token) = self.pending.remove(idx);
+        self.now = self.now.max(deadline);
+        Some(token)
+    }
+}
+
 impl<T> Window<T> {
     pub(crate) fn new(
         id: WindowId,
@@ -82,6 +173,9 @@ impl<T> Window<T> {
             invalid: Region::EMPTY,
             title: pending.title,
             transparent: pending.transparent,
+            active: true,
+            over_budget: false,
+            window_env: None,
             menu: pending.menu,
             context_menu: None,
             last_anim: None,
@@ -89,6 +183,7 @@ impl<T> Window<T> {
             focus: None,
             handle,
             timers: HashMap::new(),
+            simulated_timers: None,
             ext_handle,
             ime_handlers: Vec::new(),
             ime_focus_change: None,
@@ -146,6 +241,32 @@ impl<T: Data> Window<T> {
         }
     }
 
+    /// Record whether this is the active window.
+    ///
+    /// Widgets observe the change through [`theme::WINDOW_IS_ACTIVE`] on the next update.
+    pub(crate) fn set_active(&mut self, active: bool) {
+        if self.active != active {
+            self.active = active;
+            self.window_env = None;
+        }
+    }
+
+    /// The app env with the values specific to this window added.
+    ///
+    /// This is cached, so that widgets see the same env from one pass to the next
+    /// and don't treat every pass as an env change.
+    fn window_env(&mut self, env: &Env) -> Env {
+        if let Some((base, derived)) = &self.window_env {
+            // Entry points call each other, so `env` may already be the derived env.
+            if base.same(env) || derived.same(env) {
+                return derived.clone();
+            }
+        }
+        let derived = env.clone().adding(theme::WINDOW_IS_ACTIVE, self.active);
+        self.window_env = Some((env.clone(), derived.clone()));
+        derived
+    }
+
     fn post_event_processing(
         &mut self,
         widget_state: &mut WidgetState,
@@ -238,6 +359,7 @@ impl<T: Data> Window<T> {
         data: &mut T,
         env: &Env,
     ) -> Handled {
+        let env = &self.window_env(env);
         match &event {
             Event::WindowSize(size) => self.size = *size,
             Event::MouseDown(e) | Event::MouseUp(e) | Event::MouseMove(e) | Event::Wheel(e) => {
@@ -280,6 +402,7 @@ impl<T: Data> Window<T> {
                 &mut self.timers,
                 &mut self.pending_text_registrations,
             );
+            state.simulated_timers = self.simulated_timers.as_mut();
             let mut notifications = VecDeque::new();
             let mut ctx = EventCtx {
                 state: &mut state,
@@ -339,6 +462,7 @@ impl<T: Data> Window<T> {
         env: &Env,
         process_commands: bool,
     ) {
+        let env = &self.window_env(env);
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
         let mut state = ContextState::new::<T>(
             queue,
@@ -349,6 +473,7 @@ impl<T: Data> Window<T> {
             &mut self.timers,
             &mut self.pending_text_registrations,
         );
+        state.simulated_timers = self.simulated_timers.as_mut();
         let mut ctx = LifeCycleCtx {
             state: &mut state,
             widget_state: &mut widget_state,
@@ -364,6 +489,7 @@ impl<T: Data> Window<T> {
     }
 
     pub(crate) fn update(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
+        let env = &self.window_env(env);
         self.update_title(data, env);
 
         let mut widget_state = WidgetState::new(self.root.id(), Some(self.size));
@@ -376,6 +502,7 @@ impl<T: Data> Window<T> {
             &mut self.timers,
             &mut self.pending_text_registrations,
         );
+        state.simulated_timers = self.simulated_timers.as_mut();
         let mut update_ctx = UpdateCtx {
             widget_state: &mut widget_state,
             state: &mut state,
@@ -441,6 +568,7 @@ impl<T: Data> Window<T> {
         data: &T,
         env: &Env,
     ) {
+        let env = &self.window_env(env);
         if self.root.state().needs_layout {
             self.layout(queue, data, env);
         }
@@ -451,7 +579,7 @@ impl<T: Data> Window<T> {
                 if self.transparent {
                     Color::TRANSPARENT
                 } else {
-                    env.get(crate::theme::WINDOW_BACKGROUND_COLOR)
+                    env.get(theme::WINDOW_BACKGROUND_COLOR)
                 },
             );
         }
@@ -469,6 +597,7 @@ impl<T: Data> Window<T> {
             &mut self.timers,
             &mut self.pending_text_registrations,
         );
+        state.simulated_timers = self.simulated_timers.as_mut();
         let mut layout_ctx = LayoutCtx {
             state: &mut state,
             widget_state: &mut widget_state,
@@ -500,6 +629,7 @@ impl<T: Data> Window<T> {
     /// only expose `layout` for testing; normally it is called as part of `do_paint`
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) fn just_layout(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
+        let env = &self.window_env(env);
         self.layout(queue, data, env)
     }
 
@@ -521,6 +651,17 @@ impl<T: Data> Window<T> {
             &mut self.timers,
             &mut self.pending_text_registrations,
         );
+        let budget = PaintBudget {
+            epoch: Instant::now(),
+            deadline: Duration::ZERO,
+            over_budget: self.over_budget,
+        };
+        let start = budget.now(self.simulated_timers.as_ref());
+        state.simulated_timers = self.simulated_timers.as_mut();
+        state.paint_budget = Some(PaintBudget {
+            deadline: start + FRAME_BUDGET,
+            ..budget
+        });
         let mut ctx = PaintCtx {
             render_ctx: piet,
             state: &mut state,
@@ -547,6 +688,12 @@ impl<T: Data> Window<T> {
             });
         }
 
+        let elapsed = budget.now(ctx.state.simulated_timers.as_deref()) - start;
+        self.over_budget = elapsed > FRAME_BUDGET;
+        if self.over_budget {
+            tracing::debug!("Painting took {:?}, more than the frame budget", elapsed);
+        }
+
         if self.wants_animation_frame() {
             self.handle.request_anim_frame();
         }